@@ -1,9 +1,16 @@
 use crate::config::Config;
+use crate::i18n::{Locale, Msg};
+use crate::progress::Progress;
+use crate::prompts::ReviewPrompt;
+use crate::triage::Triage;
+use crate::{consensus, parse, schema, tokens};
 use anyhow::{Context, Result};
+use colored::*;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Debug, Clone)]
 struct OpenAIMessage {
     role: String,
     content: String,
@@ -13,6 +20,28 @@ struct OpenAIMessage {
 struct OpenAIRequest {
     model: String,
     messages: Vec<OpenAIMessage>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<OpenAIStreamOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+    /// OpenAI 已弃用 `max_tokens`，推理模型只接受该字段
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_completion_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+}
+
+#[derive(Serialize, Debug)]
+struct OpenAIStreamOptions {
+    include_usage: bool,
 }
 
 #[derive(Deserialize)]
@@ -38,6 +67,24 @@ struct OpenAIMessageContent {
     content: String,
 }
 
+/// SSE 流式响应中的单个分片（`data: {...}`）。
+#[derive(Deserialize)]
+struct OpenAIStreamChunk {
+    #[serde(default)]
+    choices: Vec<OpenAIStreamChoice>,
+    usage: Option<OpenAIUsage>,
+}
+
+#[derive(Deserialize)]
+struct OpenAIStreamChoice {
+    delta: OpenAIStreamDelta,
+}
+
+#[derive(Deserialize)]
+struct OpenAIStreamDelta {
+    content: Option<String>,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct Issue {
     pub severity: String,
@@ -45,65 +92,671 @@ pub struct Issue {
     pub description: String,
     pub suggestion: String,
     pub location: String,
+    /// 修复该问题的 unified diff 补丁（可选），由 `fix` 子命令或交互模式应用
+    #[serde(default)]
+    pub patch: Option<String>,
+    /// 多模型共识审查时，提出该问题的模型
+    #[serde(skip)]
+    pub models: Vec<String>,
 }
 
+// 字段变更时需要同步更新 `schema::check_result_schema`
 #[derive(Deserialize, Debug)]
 pub struct AiCheckResult {
     pub result: String,
     #[serde(default)]
     pub meme_comment: Option<String>,
     pub list: Vec<Issue>,
+    /// 实际给出结论的模型（启用备用模型或分块审查时可能不止一个）
+    #[serde(skip)]
+    pub models: Vec<String>,
+    /// 按阶段和模型统计的 Token 用量
     #[serde(skip)]
-    pub usage: Option<TokenUsage>,
+    pub usage: Vec<ModelUsage>,
+    /// 两阶段审查中每个 diff 块的分流决定
+    #[serde(skip)]
+    pub triage: Vec<TriageDecision>,
 }
 
-#[derive(Debug)]
+impl AiCheckResult {
+    /// 合并多个 diff 块的审查结果：任一块被拒绝则整体拒绝，问题列表与 Token 用量累加。
+    pub fn merge(self, other: AiCheckResult) -> AiCheckResult {
+        let result = if self.is_approved() && other.is_approved() {
+            "YES"
+        } else {
+            "NO"
+        };
+
+        let meme_comment = match (self.meme_comment, other.meme_comment) {
+            (Some(a), Some(b)) => Some(format!("{}\n{}", a, b)),
+            (a, b) => a.or(b),
+        };
+
+        let mut list = self.list;
+        list.extend(other.list);
+
+        let mut models = self.models;
+        for model in other.models {
+            if !models.contains(&model) {
+                models.push(model);
+            }
+        }
+
+        let mut usage = self.usage;
+        for entry in other.usage {
+            ModelUsage::accumulate(&mut usage, entry);
+        }
+
+        let mut triage = self.triage;
+        triage.extend(other.triage);
+
+        AiCheckResult {
+            result: result.to_string(),
+            meme_comment,
+            list,
+            models,
+            usage,
+            triage,
+        }
+    }
+
+    /// 记录给出该结果的模型及其用量。
+    fn produced_by(mut self, model: &str, usage: Option<TokenUsage>) -> AiCheckResult {
+        self.models = vec![model.to_string()];
+        self.usage = review_usage(model, usage);
+        self
+    }
+
+    /// 所有模型的 Token 用量合计；没有任何用量信息时返回 None。
+    pub fn total_usage(&self) -> Option<TokenUsage> {
+        self.usage
+            .iter()
+            .fold(None, |total, entry| TokenUsage::merge(total, Some(entry.usage.clone())))
+    }
+
+    /// 模型给出的结论是否为通过（YES）。
+    pub fn is_approved(&self) -> bool {
+        self.result.to_uppercase().contains("YES")
+    }
+
+    /// 只包含本地检查结果、未调用模型时使用的空结果。
+    pub fn local() -> AiCheckResult {
+        AiCheckResult {
+            result: "YES".to_string(),
+            meme_comment: None,
+            list: Vec::new(),
+            models: Vec::new(),
+            usage: Vec::new(),
+            triage: Vec::new(),
+        }
+    }
+
+    /// 把本地规则发现的问题放到列表最前面；其中有 high 级别问题时整体拒绝。
+    pub fn prepend_local_issues(&mut self, issues: Vec<Issue>) {
+        if issues.iter().any(|i| i.severity.eq_ignore_ascii_case("high")) {
+            self.result = "NO".to_string();
+        }
+        self.list.splice(0..0, issues);
+    }
+}
+
+/// 单个模型在普通审查阶段的用量条目，没有用量信息时为空。
+fn review_usage(model: &str, usage: Option<TokenUsage>) -> Vec<ModelUsage> {
+    usage
+        .map(|usage| ModelUsage {
+            stage: ModelUsage::REVIEW.to_string(),
+            model: model.to_string(),
+            usage,
+        })
+        .into_iter()
+        .collect()
+}
+
+/// 某个审查阶段中一个模型的 Token 用量。
+#[derive(Debug, Clone)]
+pub struct ModelUsage {
+    pub stage: String,
+    pub model: String,
+    pub usage: TokenUsage,
+}
+
+impl ModelUsage {
+    /// 普通审查（未启用两阶段审查，或分流后未升级的块）
+    pub const REVIEW: &'static str = "review";
+    /// 两阶段审查中的风险分流
+    pub const TRIAGE: &'static str = "triage";
+    /// 两阶段审查中升级到强模型的深度审查
+    pub const DEEP: &'static str = "deep";
+
+    /// 把用量累加到阶段和模型都相同的条目上，没有则新增。
+    pub fn accumulate(entries: &mut Vec<ModelUsage>, entry: ModelUsage) {
+        match entries
+            .iter_mut()
+            .find(|e| e.stage == entry.stage && e.model == entry.model)
+        {
+            Some(existing) => existing.usage.add(&entry.usage),
+            None => entries.push(entry),
+        }
+    }
+}
+
+/// 两阶段审查中对一个 diff 块的分流决定。
+#[derive(Debug, Clone)]
+pub struct TriageDecision {
+    pub risk: String,
+    pub categories: Vec<String>,
+    pub reason: String,
+    /// 最终审查该块的模型
+    pub model: String,
+    pub escalated: bool,
+}
+
+#[derive(Debug, Clone)]
 pub struct TokenUsage {
     pub prompt_tokens: u32,
     pub completion_tokens: u32,
     pub total_tokens: u32,
 }
 
+impl TokenUsage {
+    /// 合并多次请求（例如修复 JSON 的重试）的用量。
+    fn merge(total: Option<TokenUsage>, next: Option<TokenUsage>) -> Option<TokenUsage> {
+        match (total, next) {
+            (Some(mut a), Some(b)) => {
+                a.add(&b);
+                Some(a)
+            }
+            (a, b) => a.or(b),
+        }
+    }
+
+    pub fn add(&mut self, other: &TokenUsage) {
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+        self.total_tokens += other.total_tokens;
+    }
+}
+
+/// 风险分流时用户消息的前缀，截断 diff 时需要把它计入预算。
+fn diff_prefix(config: &Config) -> &'static str {
+    match config.prompt_lang {
+        Locale::ZhCn => "这是需要审查的 git diff:\n\n",
+        Locale::En => "Here is the git diff to review:\n\n",
+    }
+}
+
+/// 暂存变更摘要的标题，附在生成和审查提交信息的请求中。
+fn summary_label(config: &Config) -> &'static str {
+    match config.prompt_lang {
+        Locale::ZhCn => "暂存变更摘要 (git diff --cached --stat):",
+        Locale::En => "Summary of staged changes (git diff --cached --stat):",
+    }
+}
+
+/// 审查一个 diff 块。调用方负责用 `chunk::split_diff` 保证其不超过 Token 预算。
 pub async fn call_ai_check(
+    config: &Config,
+    prompt: &ReviewPrompt,
+    diff: &str,
+) -> Result<AiCheckResult> {
+    let system_prompt = prompt.system.clone();
+    let user_content = prompt.user_message(diff);
+    if config.consensus_models.is_empty() {
+        request_check(config, system_prompt, user_content).await
+    } else {
+        consensus::review(config, system_prompt, user_content).await
+    }
+}
+
+/// 让分流模型评估一个 diff 块的风险。diff 超出该模型的预算时截断，分流不需要看全部内容。
+pub async fn call_triage(
     config: &Config,
     system_prompt: String,
     diff: String,
-) -> Result<AiCheckResult> {
-    let client = Client::new();
+) -> Result<(Triage, Option<TokenUsage>)> {
+    let prefix = diff_prefix(config);
+    let budget = tokens::chunk_budget(config, &system_prompt, prefix);
+    let diff = tokens::truncate(&config.model, &diff, budget);
+
+    let response_format = match ResponseMode::from_config(config) {
+        ResponseMode::Text => None,
+        _ => Some(json!({ "type": "json_object" })),
+    };
+    let (content, usage) = send_chat(
+        config,
+        chat_messages(system_prompt, format!("{}{}", prefix, diff)),
+        response_format,
+        None,
+    )
+    .await?;
+
+    let triage = parse::parse_triage(&content)
+        .with_context(|| Msg::TriageParseFailed.fill(&[("content", content.clone())]))?;
+    Ok((triage, usage))
+}
+
+/// 根据暂存的 diff 生成一条提交信息，返回纯文本和 Token 使用情况。
+pub async fn call_message_suggestion(
+    config: &Config,
+    system_prompt: String,
+    diff: String,
+    diff_summary: &str,
+) -> Result<(String, Option<TokenUsage>)> {
+    let diff_label = match config.prompt_lang {
+        Locale::ZhCn => "暂存的 git diff:",
+        Locale::En => "Staged git diff:",
+    };
+    let prefix = format!(
+        "{}\n\n{}\n\n{}\n\n",
+        summary_label(config),
+        diff_summary,
+        diff_label
+    );
 
-    // 如果 diff 太长，根据 max_chunk_size 进行截断（粗略估计）
-    // 用户配置了 "maxChunkSize"，我们将其作为 diff 的字符限制。
-    let diff_content = if diff.len() > config.max_chunk_size {
-        // 在实际应用中，我们可能会拆分或总结。这里我们进行截断并在日志/输出中警告。
-        // 目前，只取前 N 个字符。
+    // 生成提交信息只需要一次请求，超出预算的部分直接截断
+    let budget = tokens::chunk_budget(config, &system_prompt, &prefix);
+    let mut diff_content = tokens::truncate(&config.model, &diff, budget);
+    if diff_content.len() < diff.len() {
+        diff_content.push_str("\n\n[Diff truncated due to size limit...]");
+    }
+
+    let (content, usage) = send_chat(
+        config,
+        chat_messages(system_prompt, format!("{}{}", prefix, diff_content)),
+        None,
+        None,
+    )
+    .await?;
+
+    Ok((parse::strip_code_fence(&content).to_string(), usage))
+}
+
+/// 审查提交信息，附带暂存变更的摘要供模型判断信息是否与改动相符。
+pub async fn call_commit_msg_check(
+    config: &Config,
+    system_prompt: String,
+    message: &str,
+    diff_summary: &str,
+) -> Result<AiCheckResult> {
+    let message_label = match config.prompt_lang {
+        Locale::ZhCn => "这是需要审查的提交信息:",
+        Locale::En => "Here is the commit message to review:",
+    };
+    request_check(
+        config,
+        system_prompt,
         format!(
-            "{}\n\n[Diff truncated due to size limit...]",
-            &diff[..config.max_chunk_size]
+            "{}\n\n{}\n\n{}\n\n{}",
+            message_label,
+            message,
+            summary_label(config),
+            diff_summary
+        ),
+    )
+    .await
+}
+
+/// 依次尝试主模型和备用模型，返回第一个成功给出结论的结果。
+///
+/// 网络错误、超时、5xx/408/429 以及修正后仍无法解析（或不符合 Schema）的输出会切换到
+/// 下一个模型；其他 4xx（如 Key 无效、请求参数错误）换模型通常也无济于事，直接返回。
+/// 失败的模型消耗的 Token 计入最终结果，全部失败时随 `FailedCheck` 返回。
+async fn request_check(
+    config: &Config,
+    system_prompt: String,
+    user_content: String,
+) -> Result<AiCheckResult> {
+    let endpoints = config.endpoints();
+    let mut remaining = endpoints.iter().peekable();
+    let mut spent = Vec::new();
+
+    while let Some(endpoint) = remaining.next() {
+        let endpoint_config = config.with_endpoint(endpoint);
+        let err = match request_check_once(
+            &endpoint_config,
+            system_prompt.clone(),
+            user_content.clone(),
+            None,
         )
-    } else {
-        diff
-    };
+        .await
+        {
+            Ok(mut result) => {
+                for entry in spent {
+                    ModelUsage::accumulate(&mut result.usage, entry);
+                }
+                return Ok(result);
+            }
+            Err(e) => e,
+        };
 
-    let request_body = OpenAIRequest {
-        model: config.model.clone(),
-        messages: vec![
-            OpenAIMessage {
-                role: "system".to_string(),
-                content: system_prompt,
-            },
-            OpenAIMessage {
-                role: "user".to_string(),
-                content: format!("这是需要审查的 git diff:\n\n{}", diff_content),
+        match remaining.peek() {
+            Some(next) if is_retryable(&err) => eprintln!(
+                "{} {}",
+                Msg::Warning.text().yellow(),
+                Msg::FallbackModel.fill(&[
+                    ("model", endpoint.model.clone()),
+                    ("error", format!("{:#}", err)),
+                    ("next", next.model.clone()),
+                ])
+            ),
+            _ => return Err(FailedCheck::wrap(err, spent).into()),
+        }
+        spent.extend(spent_usage(&err).iter().cloned());
+    }
+
+    unreachable!("endpoints 至少包含主模型")
+}
+
+/// 审查失败，附带失败前已经消耗的 Token 用量（修正重试、切换备用模型、前面的 diff 块），
+/// 调用方仍可以把它们计入用量记录。
+#[derive(Debug)]
+pub struct FailedCheck {
+    error: anyhow::Error,
+    pub usage: Vec<ModelUsage>,
+}
+
+impl FailedCheck {
+    /// 给错误附加用量；错误本身已经是 `FailedCheck` 时合并两者的用量。
+    pub fn wrap(error: anyhow::Error, usage: Vec<ModelUsage>) -> FailedCheck {
+        match error.downcast::<FailedCheck>() {
+            Ok(mut failed) => {
+                for entry in usage {
+                    ModelUsage::accumulate(&mut failed.usage, entry);
+                }
+                failed
+            }
+            Err(error) => FailedCheck { error, usage },
+        }
+    }
+}
+
+impl std::fmt::Display for FailedCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:#}", self.error)
+    }
+}
+
+impl std::error::Error for FailedCheck {}
+
+/// 错误中附带的已消耗用量，没有时为空。
+pub fn spent_usage(err: &anyhow::Error) -> &[ModelUsage] {
+    err.downcast_ref::<FailedCheck>()
+        .map_or(&[], |failed| failed.usage.as_slice())
+}
+
+/// 服务端返回的非成功状态码。
+#[derive(Debug)]
+struct ApiStatusError {
+    status: reqwest::StatusCode,
+    body: String,
+}
+
+impl std::fmt::Display for ApiStatusError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&Msg::ApiStatusFailed.fill(&[
+            ("status", self.status.to_string()),
+            ("body", self.body.clone()),
+        ]))
+    }
+}
+
+impl std::error::Error for ApiStatusError {}
+
+/// 请求修正后仍无法解析的模型输出，附带最后一次的原始内容。
+#[derive(Debug)]
+struct UnparseableOutput {
+    content: String,
+}
+
+impl std::fmt::Display for UnparseableOutput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&Msg::OutputParseFailed.fill(&[("content", self.content.clone())]))
+    }
+}
+
+/// 换一个模型可能成功的错误：网络错误、超时、5xx/408/429，以及修正后仍无法解析的输出。
+fn is_retryable(err: &anyhow::Error) -> bool {
+    let err = err
+        .downcast_ref::<FailedCheck>()
+        .map_or(err, |failed| &failed.error);
+
+    if err.downcast_ref::<UnparseableOutput>().is_some() {
+        return true;
+    }
+
+    if let Some(e) = err.downcast_ref::<ApiStatusError>() {
+        return e.status.is_server_error()
+            || e.status == reqwest::StatusCode::REQUEST_TIMEOUT
+            || e.status == reqwest::StatusCode::TOO_MANY_REQUESTS;
+    }
+
+    err.chain().any(|cause| {
+        cause
+            .downcast_ref::<reqwest::Error>()
+            .is_some_and(|e| e.is_timeout() || e.is_connect() || e.is_request() || e.is_body())
+    })
+}
+
+/// 向单个模型发送一次对话请求，并将模型输出解析为 `AiCheckResult`。
+///
+/// `progress` 为 None 时请求自己显示进度行；并发请求多个模型时由调用方传入共用的进度行。
+pub async fn request_check_once(
+    config: &Config,
+    system_prompt: String,
+    user_content: String,
+    progress: Option<&Progress>,
+) -> Result<AiCheckResult> {
+    let mode = ResponseMode::from_config(config);
+    let mut messages = chat_messages(system_prompt, user_content);
+    let mut usage = None;
+    let mut attempt = 0;
+
+    loop {
+        let reply = send_chat(config, messages.clone(), mode.request_format(), progress).await;
+        let (content, next_usage) = match reply {
+            Ok(reply) => reply,
+            Err(e) => return Err(FailedCheck::wrap(e, review_usage(&config.model, usage)).into()),
+        };
+        usage = TokenUsage::merge(usage, next_usage);
+
+        let err = match parse::parse_check_result(&content, mode != ResponseMode::Text) {
+            Ok(check_result) => return Ok(check_result.produced_by(&config.model, usage)),
+            Err(e) => e,
+        };
+
+        // 尾随逗号之类的语法问题本地就能修好，不必再请求一次；
+        // 取值不符合 Schema（如 severity 为 critical）时交给模型修正
+        if err.downcast_ref::<parse::SchemaError>().is_none() {
+            if let Ok(check_result) = parse::parse_lenient(&content) {
+                return Ok(check_result.produced_by(&config.model, usage));
+            }
+        }
+
+        if attempt >= config.repair_attempts {
+            let err = err.context(UnparseableOutput { content });
+            return Err(FailedCheck::wrap(err, review_usage(&config.model, usage)).into());
+        }
+        attempt += 1;
+
+        eprintln!(
+            "{} {}",
+            Msg::Warning.text().yellow(),
+            Msg::RepairingOutput.fill(&[
+                ("error", format!("{:#}", err)),
+                ("attempt", attempt.to_string()),
+                ("max", config.repair_attempts.to_string()),
+            ])
+        );
+
+        // 把错误和原始输出交还给模型，要求只返回修正后的 JSON
+        messages.push(OpenAIMessage {
+            role: "assistant".to_string(),
+            content,
+        });
+        messages.push(OpenAIMessage {
+            role: "user".to_string(),
+            content: match config.prompt_lang {
+                Locale::ZhCn => format!(
+                    "你上一次的输出无法解析为要求的 JSON，错误信息：{:#}。请只返回修正后的完整 JSON，字段与之前要求的完全一致，不要包含任何其他文字或代码块标记。",
+                    err
+                ),
+                Locale::En => format!(
+                    "Your previous output could not be parsed as the required JSON: {:#}. Return only the corrected, complete JSON with exactly the same fields as before, without any other text or code fences.",
+                    err
+                ),
             },
-        ],
+        });
+    }
+}
+
+fn chat_messages(system_prompt: String, user_content: String) -> Vec<OpenAIMessage> {
+    vec![
+        OpenAIMessage {
+            role: "system".to_string(),
+            content: system_prompt,
+        },
+        OpenAIMessage {
+            role: "user".to_string(),
+            content: user_content,
+        },
+    ]
+}
+
+/// 请求模型以何种方式返回 JSON。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResponseMode {
+    /// `response_format: json_schema`，由服务端按 Schema 约束输出
+    JsonSchema,
+    /// `response_format: json_object`，只保证输出是合法 JSON
+    JsonObject,
+    /// 不支持结构化输出的服务，从文本中容错提取 JSON
+    Text,
+}
+
+impl ResponseMode {
+    /// 根据 `AI_CHECK_RESPONSE_FORMAT` 选择；`auto` 时按已知服务商判断。
+    fn from_config(config: &Config) -> Self {
+        match config.response_format.as_str() {
+            "json_schema" => ResponseMode::JsonSchema,
+            "json_object" => ResponseMode::JsonObject,
+            "none" | "text" => ResponseMode::Text,
+            _ if config.base_url.contains("api.openai.com") => ResponseMode::JsonSchema,
+            _ if config.base_url.contains("deepseek") => ResponseMode::JsonObject,
+            _ => ResponseMode::Text,
+        }
+    }
+
+    fn request_format(self) -> Option<serde_json::Value> {
+        match self {
+            ResponseMode::JsonSchema => Some(json!({
+                "type": "json_schema",
+                "json_schema": {
+                    "name": "ai_check_result",
+                    "strict": true,
+                    "schema": schema::check_result_schema()
+                }
+            })),
+            ResponseMode::JsonObject => Some(json!({ "type": "json_object" })),
+            ResponseMode::Text => None,
+        }
+    }
+}
+
+fn build_request(
+    config: &Config,
+    messages: Vec<OpenAIMessage>,
+    response_format: Option<serde_json::Value>,
+) -> Result<serde_json::Value> {
+    let openai = config.base_url.contains("api.openai.com");
+    let request = OpenAIRequest {
+        stream: config.stream,
+        stream_options: config
+            .stream
+            .then_some(OpenAIStreamOptions { include_usage: true }),
+        response_format,
+        temperature: config.temperature,
+        top_p: config.top_p,
+        max_tokens: config.max_tokens.filter(|_| !openai),
+        max_completion_tokens: config.max_tokens.filter(|_| openai),
+        seed: config.seed,
+        model: config.model.clone(),
+        messages,
     };
 
-    // 构造 URL。处理尾部斜杠。
-    let url = if config.base_url.ends_with('/') {
+    // 额外字段最后合并，允许覆盖上面的任何参数
+    let mut body = serde_json::to_value(request)?;
+    if let Some(object) = body.as_object_mut() {
+        object.extend(config.extra_body.clone());
+    }
+    Ok(body)
+}
+
+/// 本次运行使用的模型参数（只包含显式配置的项），用于报告和用量记录，便于复现。
+pub fn model_params(config: &Config) -> serde_json::Map<String, serde_json::Value> {
+    let mut params = serde_json::Map::new();
+    if let Some(v) = config.temperature {
+        params.insert("temperature".to_string(), json!(v));
+    }
+    if let Some(v) = config.top_p {
+        params.insert("top_p".to_string(), json!(v));
+    }
+    if let Some(v) = config.max_tokens {
+        params.insert("max_tokens".to_string(), json!(v));
+    }
+    if let Some(v) = config.seed {
+        params.insert("seed".to_string(), json!(v));
+    }
+    params.extend(config.extra_body.clone());
+    params
+}
+
+/// 构造 URL。处理尾部斜杠。
+pub fn completions_url(config: &Config) -> String {
+    if config.base_url.ends_with('/') {
         format!("{}chat/completions", config.base_url)
     } else {
         format!("{}/chat/completions", config.base_url)
+    }
+}
+
+/// 返回审查一个 diff 块时将要发送的请求体，与 `call_ai_check` 实际发送的内容一致，
+/// 但不发起网络请求（用于 `check --dry-run`）。
+pub fn preview_check_request(
+    config: &Config,
+    prompt: &ReviewPrompt,
+    diff: &str,
+) -> Result<serde_json::Value> {
+    let mode = ResponseMode::from_config(config);
+    build_request(
+        config,
+        chat_messages(prompt.system.clone(), prompt.user_message(diff)),
+        mode.request_format(),
+    )
+}
+
+/// 发送一次对话请求，返回模型输出的原始文本和 Token 使用情况。
+///
+/// 未传入 `progress` 时在等待期间显示自己的进度行。
+async fn send_chat(
+    config: &Config,
+    messages: Vec<OpenAIMessage>,
+    response_format: Option<serde_json::Value>,
+    progress: Option<&Progress>,
+) -> Result<(String, Option<TokenUsage>)> {
+    let mut client = Client::builder();
+    if config.timeout_secs > 0 {
+        client = client.timeout(std::time::Duration::from_secs(config.timeout_secs));
+    }
+    let client = client.build().context(Msg::HttpClientFailed.text())?;
+
+    let request_body = build_request(config, messages, response_format)?;
+    let url = completions_url(config);
+
+    let own = match progress {
+        Some(_) => None,
+        None => Some(Progress::start(Msg::ProgressWaiting.text())),
     };
+    let progress = progress.or(own.as_ref()).expect("进度行已创建");
 
     let res = client
         .post(&url)
@@ -112,55 +765,113 @@ pub async fn call_ai_check(
         .json(&request_body)
         .send()
         .await
-        .context("发送请求给 AI 提供商失败")?;
+        .context(Msg::ApiRequestFailed.text())?;
 
-    if !res.status().is_success() {
-        let error_text = res.text().await?;
-        return Err(anyhow::anyhow!("AI API 请求失败: {}", error_text));
+    let status = res.status();
+    if !status.is_success() {
+        let body = res.text().await?;
+        return Err(ApiStatusError { status, body }.into());
     }
 
+    // 部分兼容 OpenAI 的服务会忽略 stream 参数，按 Content-Type 决定如何解析
+    let is_event_stream = res
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains("text/event-stream"));
+
+    let result = if is_event_stream {
+        read_event_stream(res, &config.model, progress).await
+    } else {
+        read_json_response(res).await
+    };
+
+    if let Some(own) = own {
+        own.finish();
+    }
+    result
+}
+
+async fn read_json_response(res: reqwest::Response) -> Result<(String, Option<TokenUsage>)> {
     let response_body: OpenAIResponse = res
         .json()
         .await
-        .context("解析 AI 响应 JSON 失败")?;
+        .context(Msg::ApiResponseInvalid.text())?;
+
+    let usage = response_body.usage.map(TokenUsage::from);
 
     let content = response_body
         .choices
-        .first()
-        .context("AI 响应中没有 choices")?
+        .into_iter()
+        .next()
+        .context(Msg::ApiNoChoices.text())?
         .message
-        .content
-        .trim();
-    // 内容应该是 JSON。
-    // 有时 AI 会将其包装在 ```json ... ``` 中。我们需要去除它。
-    let json_str = if let Some(start) = content.find("```json") {
-        if content[start..].find("```").is_some() {
-            // 如果有多个块，这个逻辑有点缺陷，但通常只有一个。
-            // 让我们尝试找到第一个 '{' 和最后一个 '}'
-            find_json_bounds(content)
-        } else {
-            find_json_bounds(content)
+        .content;
+
+    Ok((content, usage))
+}
+
+/// 逐块读取 SSE 响应，累积 `delta.content`，并更新进度行。
+async fn read_event_stream(
+    mut res: reqwest::Response,
+    model: &str,
+    progress: &Progress,
+) -> Result<(String, Option<TokenUsage>)> {
+    let mut content = String::new();
+    let mut usage = None;
+    let mut buffer: Vec<u8> = Vec::new();
+    // 本次请求计入进度行的 Token 估算值
+    let mut estimated = 0usize;
+
+    while let Some(bytes) = res.chunk().await.context(Msg::StreamReadFailed.text())? {
+        buffer.extend_from_slice(&bytes);
+
+        // 事件按行分隔；最后一行可能不完整，留到下一次再处理
+        while let Some(pos) = buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&line);
+            let Some(data) = line.trim().strip_prefix("data:") else {
+                continue;
+            };
+
+            let data = data.trim();
+            if data == "[DONE]" {
+                return Ok((content, usage));
+            }
+
+            let chunk: OpenAIStreamChunk = serde_json::from_str(data)
+                .with_context(|| Msg::StreamChunkInvalid.fill(&[("data", data.to_string())]))?;
+
+            if let Some(delta) = chunk
+                .choices
+                .into_iter()
+                .next()
+                .and_then(|c| c.delta.content)
+            {
+                // 分片大小因服务而异，按内容估算 Token 数
+                let tokens = tokens::count(model, &delta);
+                estimated += tokens;
+                progress.received(tokens);
+                content.push_str(&delta);
+            }
+
+            if let Some(u) = chunk.usage {
+                progress.correct_tokens(estimated, u.completion_tokens as usize);
+                estimated = u.completion_tokens as usize;
+                usage = Some(TokenUsage::from(u));
+            }
         }
-    } else {
-        find_json_bounds(content)
-    };
+    }
 
-    let mut check_result: AiCheckResult = serde_json::from_str(json_str)
-        .context(format!("无法将 AI 输出解析为 JSON。内容: {}", content))?;
-    
-    if let Some(usage) = response_body.usage {
-        check_result.usage = Some(TokenUsage {
+    Ok((content, usage))
+}
+
+impl From<OpenAIUsage> for TokenUsage {
+    fn from(usage: OpenAIUsage) -> Self {
+        TokenUsage {
             prompt_tokens: usage.prompt_tokens,
             completion_tokens: usage.completion_tokens,
             total_tokens: usage.total_tokens,
-        });
+        }
     }
-
-    Ok(check_result)
-}
-
-fn find_json_bounds(s: &str) -> &str {
-    let start = s.find('{').unwrap_or(0);
-    let end = s.rfind('}').map(|i| i + 1).unwrap_or(s.len());
-    &s[start..end]
 }
@@ -0,0 +1,139 @@
+use crate::ai::{AiCheckResult, Issue};
+use crate::config::Config;
+use crate::i18n::Msg;
+use crate::{diff, git, usage};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// 一条被标记为误报的问题。
+///
+/// 按文件、视角和被标记代码行的内容匹配，代码行移动位置后仍然有效；
+/// 问题不指向 diff 中的新增行时退回到按位置匹配。
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Suppression {
+    pub file: String,
+    pub perspective: String,
+    #[serde(default)]
+    pub code: String,
+    pub location: String,
+    pub description: String,
+    pub added_by: String,
+    pub date: String,
+}
+
+/// 仓库中的误报基线（默认 `.aicheck/baseline.json`），可以提交到版本库与团队共享。
+pub struct Baseline {
+    path: PathBuf,
+    entries: Vec<Suppression>,
+}
+
+impl Baseline {
+    /// 读取基线文件；文件不存在时返回空基线。
+    ///
+    /// CI 中从目标分支读取（见 `git::read_repo_file`），PR 无法通过修改基线屏蔽对自己的问题。
+    /// `save` 总是写入工作区。
+    pub fn load(config: &Config) -> Result<Baseline> {
+        let path = git::backend(config)
+            .work_dir()
+            .unwrap_or_default()
+            .join(&config.baseline_file);
+
+        let entries = match git::read_repo_file(config, &config.baseline_file)? {
+            Some((content, source)) => serde_json::from_str(&content).with_context(|| {
+                Msg::BaselineParseFailed.fill(&[("path", format!("{:?}", source))])
+            })?,
+            None => Vec::new(),
+        };
+
+        Ok(Baseline { path, entries })
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    pub fn is_suppressed(&self, issue: &Issue, diff_text: &str) -> bool {
+        let key = Suppression::fingerprint(issue, diff_text);
+        self.entries.iter().any(|entry| entry.matches(&key))
+    }
+
+    pub fn add(&mut self, issue: &Issue, diff_text: &str) {
+        let key = Suppression::fingerprint(issue, diff_text);
+        if !self.entries.iter().any(|entry| entry.matches(&key)) {
+            self.entries.push(Suppression {
+                added_by: usage::author(),
+                date: usage::today(),
+                ..key
+            });
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).context(Msg::BaselineDirFailed.text())?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(&self.entries)? + "\n")
+            .with_context(|| Msg::BaselineWriteFailed.fill(&[("path", format!("{:?}", self.path))]))
+    }
+
+    /// 从结果中移除基线里的问题，返回移除的数量。
+    ///
+    /// 只有被移除的问题中有 high 级别、且移除后不再有 high 级别问题时才把结论改为通过：
+    /// 模型按提示词只会因 high 问题拒绝，拒绝的原因都已被忽略。没有移除 high 问题时
+    /// 保留模型原来的结论。
+    pub fn apply(&self, result: &mut AiCheckResult, diff_text: &str) -> usize {
+        let is_high = |issue: &Issue| issue.severity.eq_ignore_ascii_case("high");
+
+        let before = result.list.len();
+        let mut removed_high = false;
+        result.list.retain(|issue| {
+            let suppressed = self.is_suppressed(issue, diff_text);
+            removed_high |= suppressed && is_high(issue);
+            !suppressed
+        });
+
+        if removed_high && !result.list.iter().any(is_high) {
+            result.result = "YES".to_string();
+        }
+        before - result.list.len()
+    }
+}
+
+impl Suppression {
+    /// 用于匹配的字段；`added_by` 和 `date` 留空。
+    fn fingerprint(issue: &Issue, diff_text: &str) -> Suppression {
+        let (file, code) = match diff::parse_location(&issue.location) {
+            Some((file, line)) => {
+                let code = diff::added_lines(diff_text)
+                    .into_iter()
+                    .find(|l| l.file == file && l.line_no == line)
+                    .map(|l| l.content.trim().to_string())
+                    .unwrap_or_default();
+                (file.to_string(), code)
+            }
+            None => (issue.location.trim().to_string(), String::new()),
+        };
+
+        Suppression {
+            file,
+            perspective: issue.perspective.trim().to_lowercase(),
+            code,
+            location: issue.location.trim().to_string(),
+            description: issue.description.clone(),
+            added_by: String::new(),
+            date: String::new(),
+        }
+    }
+
+    fn matches(&self, key: &Suppression) -> bool {
+        self.file == key.file
+            && self.perspective == key.perspective
+            && if key.code.is_empty() {
+                self.location == key.location
+            } else {
+                self.code == key.code
+            }
+    }
+}
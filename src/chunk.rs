@@ -0,0 +1,219 @@
+use crate::tokens;
+
+/// 超出预算的单行被截断时附加的说明。
+const TRUNCATED_NOTE: &str = "\n[Line truncated due to size limit...]\n";
+
+/// 将 diff 切分为多个不超过 `budget` 个 Token 的块。
+///
+/// 优先按文件切分并把多个小文件合并到同一块；单个文件超出预算时按 hunk 切分
+/// （每块都带上文件头），单个 hunk 仍然超出时按行切分（每段都带上 `@@` 行），
+/// 只有单独一行就超出预算时才截断。第一个文件之前的说明文字
+/// （例如 combined diff 的说明）会附加在每一块的开头。
+pub fn split_diff(model: &str, diff: &str, budget: usize) -> Vec<String> {
+    let (preamble, files) = split_files(diff);
+    let budget = budget.saturating_sub(tokens::count(model, &preamble));
+
+    let mut units: Vec<(String, usize)> = Vec::new();
+    for file in files {
+        let size = tokens::count(model, &file);
+        if size <= budget {
+            units.push((file, size));
+            continue;
+        }
+
+        let (header, hunks) = split_hunks(&file);
+        let header_size = tokens::count(model, &header);
+        for hunk in hunks {
+            let hunk_size = tokens::count(model, &hunk);
+            if header_size + hunk_size <= budget {
+                units.push((format!("{}{}", header, hunk), header_size + hunk_size));
+                continue;
+            }
+
+            // hunk 过大：保留文件头和 `@@` 行，把其余行分段
+            let (hunk_head, body) = hunk.split_once('\n').unwrap_or((&hunk, ""));
+            let prefix = format!("{}{}\n", header, hunk_head);
+            let prefix_size = tokens::count(model, &prefix);
+            let room = budget.saturating_sub(prefix_size);
+
+            let mut piece = String::new();
+            let mut piece_size = 0;
+            for line in body.split_inclusive('\n') {
+                let mut line = line.to_string();
+                let mut line_size = tokens::count(model, &line);
+                if line_size > room {
+                    let keep = room.saturating_sub(tokens::count(model, TRUNCATED_NOTE));
+                    line = format!("{}{}", tokens::truncate(model, &line, keep), TRUNCATED_NOTE);
+                    line_size = room;
+                }
+                if !piece.is_empty() && piece_size + line_size > room {
+                    units.push((format!("{}{}", prefix, piece), prefix_size + piece_size));
+                    piece.clear();
+                    piece_size = 0;
+                }
+                piece.push_str(&line);
+                piece_size += line_size;
+            }
+            if !piece.is_empty() {
+                units.push((format!("{}{}", prefix, piece), prefix_size + piece_size));
+            }
+        }
+    }
+
+    // 贪心地把相邻的单元装进同一块
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut current_size = 0;
+    for (unit, size) in units {
+        if !current.is_empty() && current_size + size > budget {
+            chunks.push(format!("{}{}", preamble, current));
+            current.clear();
+            current_size = 0;
+        }
+        current.push_str(&unit);
+        current_size += size;
+    }
+    if !current.is_empty() {
+        chunks.push(format!("{}{}", preamble, current));
+    }
+
+    chunks
+}
+
+/// 按 `diff --git` / `diff --cc` 行把 diff 拆成每个文件一段，返回（前导文字, 文件段）。
+fn split_files(diff: &str) -> (String, Vec<String>) {
+    let mut preamble = String::new();
+    let mut files: Vec<String> = Vec::new();
+
+    for line in diff.split_inclusive('\n') {
+        if line.starts_with("diff --git ") || line.starts_with("diff --cc ") {
+            files.push(String::new());
+        }
+        match files.last_mut() {
+            Some(file) => file.push_str(line),
+            None => preamble.push_str(line),
+        }
+    }
+
+    (preamble, files)
+}
+
+/// 把单个文件的 diff 拆成（文件头, hunk 列表），hunk 以 `@@` 行开始。
+fn split_hunks(file: &str) -> (String, Vec<String>) {
+    let mut header = String::new();
+    let mut hunks: Vec<String> = Vec::new();
+
+    for line in file.split_inclusive('\n') {
+        if line.starts_with("@@") {
+            hunks.push(String::new());
+        }
+        match hunks.last_mut() {
+            Some(hunk) => hunk.push_str(line),
+            None => header.push_str(line),
+        }
+    }
+
+    (header, hunks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODEL: &str = "gpt-4o";
+
+    fn file_diff(name: &str, hunks: &[(usize, usize)]) -> String {
+        let mut diff = format!("diff --git a/{name} b/{name}\n--- a/{name}\n+++ b/{name}\n");
+        for &(start, lines) in hunks {
+            diff.push_str(&format!("@@ -{start},0 +{start},{lines} @@\n"));
+            for i in 0..lines {
+                diff.push_str(&format!("+let {name}_{start}_{i} = compute({i});\n"));
+            }
+        }
+        diff
+    }
+
+    #[test]
+    fn small_files_share_one_chunk() {
+        let diff = format!(
+            "{}{}",
+            file_diff("a.rs", &[(1, 3)]),
+            file_diff("b.rs", &[(1, 3)])
+        );
+        assert_eq!(split_diff(MODEL, &diff, 10_000), [diff]);
+    }
+
+    #[test]
+    fn files_are_split_when_they_do_not_fit_together() {
+        let a = file_diff("a.rs", &[(1, 5)]);
+        let b = file_diff("b.rs", &[(1, 5)]);
+        let budget = tokens::count(MODEL, &a).max(tokens::count(MODEL, &b)) + 1;
+        let diff = format!("{}{}", a, b);
+        assert_eq!(split_diff(MODEL, &diff, budget), [a, b]);
+    }
+
+    #[test]
+    fn large_file_is_split_by_hunk_with_file_header() {
+        let diff = file_diff("a.rs", &[(1, 5), (100, 5), (200, 5)]);
+        let (header, hunks) = split_hunks(&diff);
+        let budget = hunks
+            .iter()
+            .map(|hunk| tokens::count(MODEL, &format!("{}{}", header, hunk)))
+            .max()
+            .unwrap()
+            + 1;
+
+        let chunks = split_diff(MODEL, &diff, budget);
+        assert_eq!(chunks.len(), 3);
+        for (chunk, hunk) in chunks.iter().zip(&hunks) {
+            assert_eq!(*chunk, format!("{}{}", header, hunk));
+            assert!(tokens::count(MODEL, chunk) <= budget);
+        }
+    }
+
+    #[test]
+    fn large_hunk_is_split_by_line_with_hunk_header() {
+        let diff = file_diff("a.rs", &[(1, 40)]);
+        let budget = tokens::count(MODEL, &diff) / 3;
+
+        let chunks = split_diff(MODEL, &diff, budget);
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert!(chunk.starts_with(
+                "diff --git a/a.rs b/a.rs\n--- a/a.rs\n+++ b/a.rs\n@@ -1,0 +1,40 @@\n"
+            ));
+            assert!(tokens::count(MODEL, chunk) <= budget);
+        }
+        // 每一行都恰好出现在一块中
+        for i in 0..40 {
+            let line = format!("+let a.rs_1_{i} = compute({i});\n");
+            assert_eq!(
+                chunks.iter().filter(|c| c.contains(&line)).count(),
+                1,
+                "{line}"
+            );
+        }
+    }
+
+    #[test]
+    fn over_long_line_is_truncated() {
+        let long = "x".repeat(4_000);
+        let diff =
+            format!("diff --git a/a.rs b/a.rs\n--- a/a.rs\n+++ b/a.rs\n@@ -1,0 +1 @@\n+{long}\n");
+        let chunks = split_diff(MODEL, &diff, 200);
+        assert_eq!(chunks.len(), 1);
+        assert!(chunks[0].ends_with(TRUNCATED_NOTE));
+        assert!(chunks[0].len() < diff.len());
+    }
+
+    #[test]
+    fn preamble_is_repeated_in_every_chunk() {
+        let note = "[combined diff note]\n\n";
+        let a = file_diff("a.rs", &[(1, 5)]);
+        let b = file_diff("b.rs", &[(1, 5)]);
+        let budget =
+            tokens::count(MODEL, note) + tokens::count(MODEL, &a).max(tokens::count(MODEL, &b)) + 1;
+        let chunks = split_diff(MODEL, &format!("{}{}{}", note, a, b), budget);
+        assert_eq!(chunks, [format!("{}{}", note, a), format!("{}{}", note, b)]);
+    }
+}
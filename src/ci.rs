@@ -0,0 +1,258 @@
+use crate::ai::{AiCheckResult, Issue};
+use crate::config::Config;
+use crate::i18n::Msg;
+use crate::{diff, digest};
+use anyhow::{bail, Context, Result};
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+/// GitLab 默认读取的 Code Quality 报告文件名
+pub const CODE_QUALITY_REPORT: &str = "gl-code-quality-report.json";
+
+/// 当前所在的 CI 平台，根据平台预设的环境变量判断。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    GitHub,
+    GitLab,
+    Other,
+}
+
+impl Platform {
+    pub fn detect() -> Platform {
+        if env_is_true("GITHUB_ACTIONS") {
+            Platform::GitHub
+        } else if env_is_true("GITLAB_CI") {
+            Platform::GitLab
+        } else {
+            Platform::Other
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Platform::GitHub => "GitHub Actions",
+            Platform::GitLab => "GitLab CI",
+            Platform::Other => Msg::UnknownCi.text(),
+        }
+    }
+}
+
+/// 要比较的两个修订：命令行参数优先，否则从 CI 环境变量推断 PR/MR 的目标分支和源提交。
+///
+/// GitHub 的 `pull_request` 事件只提供目标分支名，需要 checkout 时带上完整历史（`fetch-depth: 0`）。
+pub fn revisions(
+    platform: Platform,
+    base: Option<String>,
+    head: Option<String>,
+) -> Result<(String, String)> {
+    let head = head.unwrap_or_else(|| match platform {
+        Platform::GitLab => non_empty_var("CI_COMMIT_SHA").unwrap_or_else(|| "HEAD".to_string()),
+        _ => "HEAD".to_string(),
+    });
+
+    if let Some(base) = base {
+        return Ok((base, head));
+    }
+
+    let base = match platform {
+        Platform::GitHub => non_empty_var("GITHUB_BASE_REF").map(|b| format!("origin/{}", b)),
+        Platform::GitLab => non_empty_var("CI_MERGE_REQUEST_DIFF_BASE_SHA")
+            .or_else(|| {
+                non_empty_var("CI_MERGE_REQUEST_TARGET_BRANCH_NAME")
+                    .map(|b| format!("origin/{}", b))
+            })
+            // 普通推送流水线：与推送前的提交比较（新分支时为全 0，不可用）
+            .or_else(|| {
+                non_empty_var("CI_COMMIT_BEFORE_SHA").filter(|sha| sha.chars().any(|c| c != '0'))
+            }),
+        Platform::Other => None,
+    };
+
+    match base {
+        Some(base) => Ok((base, head)),
+        None => bail!(Msg::CiBaseUnknown.fill(&[("platform", platform.label().to_string())])),
+    }
+}
+
+/// 是否有达到阈值（`AI_CHECK_CI_FAIL_ON`）的问题；为 none 时只报告不失败。
+///
+/// CI 中以严重级别而不是模型给出的 YES/NO 结论决定流水线是否失败。
+pub fn fails(config: &Config, result: &AiCheckResult) -> bool {
+    let threshold = match config.ci_fail_on.as_str() {
+        "none" => return false,
+        "low" => 1,
+        "medium" => 2,
+        _ => 3,
+    };
+    result.list.iter().any(|issue| rank(&issue.severity) >= threshold)
+}
+
+/// 输出 GitHub Actions 工作流命令，问题会显示在 PR 的 Files changed 中对应的行上。
+pub fn print_annotations(result: &AiCheckResult) {
+    for issue in &result.list {
+        let level = match rank(&issue.severity) {
+            3 => "error",
+            2 => "warning",
+            _ => "notice",
+        };
+
+        let mut properties = Vec::new();
+        if let Some((file, line)) = diff::parse_location(&issue.location) {
+            properties.push(format!("file={}", escape_property(file)));
+            properties.push(format!("line={}", line));
+        }
+        properties.push(format!("title={}", escape_property(&title(issue))));
+
+        println!(
+            "::{} {}::{}",
+            level,
+            properties.join(","),
+            escape_data(&message(issue))
+        );
+    }
+}
+
+/// 把结果写成 GitLab Code Quality 报告，MR 页面会据此标注新增的问题。
+pub fn write_code_quality(result: &AiCheckResult, path: &Path) -> Result<()> {
+    let entries: Vec<serde_json::Value> = result
+        .list
+        .iter()
+        .map(|issue| {
+            let (file, line) = diff::parse_location(&issue.location)
+                .unwrap_or((issue.location.trim(), 1));
+            let severity = match rank(&issue.severity) {
+                3 => "critical",
+                2 => "major",
+                1 => "minor",
+                _ => "info",
+            };
+
+            serde_json::json!({
+                "description": message(issue),
+                "check_name": format!("ai-check/{}", issue.perspective.trim().to_lowercase()),
+                "fingerprint": fingerprint(issue, file),
+                "severity": severity,
+                "location": {
+                    "path": file,
+                    "lines": { "begin": line },
+                },
+            })
+        })
+        .collect();
+
+    fs::write(path, serde_json::to_string_pretty(&entries)?)
+        .with_context(|| Msg::CodeQualityWriteFailed.fill(&[("path", format!("{:?}", path))]))
+}
+
+/// 设置了 `GITHUB_STEP_SUMMARY` 时，把审查摘要追加到工作流的 Summary 页面。
+pub fn write_step_summary(config: &Config, result: &AiCheckResult, failed: bool) -> Result<()> {
+    let Some(path) = non_empty_var("GITHUB_STEP_SUMMARY") else {
+        return Ok(());
+    };
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| Msg::StepSummaryFailed.fill(&[("path", path.clone())]))?;
+    file.write_all(summary_markdown(config, result, failed).as_bytes())?;
+    Ok(())
+}
+
+/// Markdown 格式的审查摘要，用于步骤摘要和 PR 中的汇总评论。
+pub fn summary_markdown(config: &Config, result: &AiCheckResult, failed: bool) -> String {
+    let mut md = format!("## {}\n\n", Msg::SummaryTitle);
+    let verdict = if failed {
+        Msg::SummaryFailed
+    } else {
+        Msg::SummaryPassed
+    };
+    md.push_str(&Msg::SummaryVerdict.fill(&[
+        ("verdict", verdict.text().to_string()),
+        ("threshold", config.ci_fail_on.clone()),
+    ]));
+    md.push_str("\n\n");
+    if let Some(comment) = result.meme_comment.as_deref().filter(|c| !c.trim().is_empty()) {
+        md.push_str(&format!("> {}\n\n", comment.trim()));
+    }
+
+    if result.list.is_empty() {
+        md.push_str(&format!("{}\n", Msg::SummaryNoIssues));
+    } else {
+        md.push_str(&format!("{}\n|---|---|---|---|---|\n", Msg::SummaryTableHeader));
+        for issue in &result.list {
+            md.push_str(&format!(
+                "| {} | {} | `{}` | {} | {} |\n",
+                cell(&issue.severity),
+                cell(&issue.perspective),
+                cell(&issue.location),
+                cell(&issue.description),
+                cell(&issue.suggestion)
+            ));
+        }
+    }
+
+    if let Some(total) = result.total_usage() {
+        md.push_str(&format!("\n{} {} tokens\n", Msg::TokenUsage, total.total_tokens));
+    }
+    md
+}
+
+fn rank(severity: &str) -> u8 {
+    match severity.trim().to_lowercase().as_str() {
+        "high" => 3,
+        "medium" => 2,
+        "low" => 1,
+        _ => 0,
+    }
+}
+
+fn title(issue: &Issue) -> String {
+    format!(
+        "[{}] {}",
+        issue.severity.trim().to_lowercase(),
+        issue.perspective.trim()
+    )
+}
+
+fn message(issue: &Issue) -> String {
+    if issue.suggestion.trim().is_empty() {
+        issue.description.clone()
+    } else {
+        format!("{}\n{} {}", issue.description, Msg::SuggestionLabel, issue.suggestion)
+    }
+}
+
+/// 按文件、视角和描述生成稳定的指纹，GitLab 用它比较 MR 前后新增或修复的问题。
+fn fingerprint(issue: &Issue, file: &str) -> String {
+    digest::fingerprint(&[
+        file,
+        &digest::normalize(&issue.perspective),
+        &digest::normalize(&issue.description),
+    ])
+}
+
+/// 工作流命令消息部分的转义规则。
+fn escape_data(s: &str) -> String {
+    s.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+/// 工作流命令属性部分还需要转义 `:` 和 `,`。
+fn escape_property(s: &str) -> String {
+    escape_data(s).replace(':', "%3A").replace(',', "%2C")
+}
+
+/// Markdown 表格单元格中不能出现换行和未转义的 `|`。
+fn cell(s: &str) -> String {
+    s.trim().replace('|', "\\|").replace('\n', "<br>")
+}
+
+fn env_is_true(name: &str) -> bool {
+    env::var(name).is_ok_and(|v| v.eq_ignore_ascii_case("true"))
+}
+
+fn non_empty_var(name: &str) -> Option<String> {
+    env::var(name).ok().filter(|v| !v.trim().is_empty())
+}
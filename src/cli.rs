@@ -1,4 +1,5 @@
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(name = "ai_git_pre_commit")]
@@ -11,11 +12,104 @@ pub struct Cli {
 #[derive(Subcommand)]
 pub enum Commands {
     /// 安装 pre-commit 钩子和配置
-    Install,
+    Install {
+        /// 同时安装 commit-msg 钩子，审查提交信息
+        #[arg(long)]
+        commit_msg: bool,
+        /// 同时安装 prepare-commit-msg 钩子，自动生成提交信息
+        #[arg(long)]
+        prepare_commit_msg: bool,
+    },
     /// 卸载 pre-commit 钩子
     Uninstall,
     /// 更新二进制文件到最新版本
     Update,
     /// 运行检查（如果未提供命令，则为默认行为）
-    Check,
+    Check(CheckArgs),
+    /// 审查任意文件或目录（整个文件视为新增内容），不要求已暂存或位于 git 仓库中
+    Review(ReviewArgs),
+    /// 在 CI 中审查 PR/MR 引入的变更，输出 GitHub 注解或 GitLab Code Quality 报告
+    Ci(CiArgs),
+    /// 审查提交信息（由 commit-msg 钩子调用）
+    CommitMsg {
+        /// git 传入的提交信息文件路径
+        file: PathBuf,
+    },
+    /// 根据暂存区生成提交信息（由 prepare-commit-msg 钩子调用）
+    PrepareCommitMsg {
+        /// git 传入的提交信息文件路径
+        file: PathBuf,
+        /// 提交信息来源（message/template/merge/squash/commit）
+        source: Option<String>,
+        /// amend 时对应的提交
+        sha: Option<String>,
+    },
+    /// 根据暂存区生成一条提交信息并打印
+    SuggestMessage,
+    /// 逐个确认并应用上一次检查给出的修复补丁（应用到工作区并重新暂存）
+    Fix,
+    /// 查看审查使用的提示词
+    Prompt {
+        #[command(subcommand)]
+        action: PromptAction,
+    },
+    /// 按日期、提交者和模型汇总本仓库的 Token 用量和费用
+    Usage {
+        /// 只统计最近 N 天
+        #[arg(long)]
+        days: Option<u64>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PromptAction {
+    /// 用暂存区的变更渲染当前生效的系统提示词和用户消息
+    Print {
+        /// 输出未渲染的模板原文，可以复制到 .aicheck/prompts/ 下修改
+        #[arg(long)]
+        template: bool,
+    },
+}
+
+#[derive(Args, Default)]
+pub struct CheckArgs {
+    /// 只构建并输出将要发送的完整请求（提示词、分块、模型参数、Token 估算），不调用 API
+    #[arg(long)]
+    pub dry_run: bool,
+    /// 配合 --dry-run，把请求写入文件而不是打印到终端
+    #[arg(long, requires = "dry_run")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct ReviewArgs {
+    /// 要审查的文件或目录，目录会递归展开（跳过隐藏目录）
+    #[arg(required = true)]
+    pub paths: Vec<PathBuf>,
+    #[command(flatten)]
+    pub check: CheckArgs,
+}
+
+#[derive(Args)]
+pub struct CiArgs {
+    /// 比较的基准（PR 的目标分支或提交），默认从 CI 环境变量推断
+    #[arg(long)]
+    pub base: Option<String>,
+    /// 被审查的提交，默认为 HEAD（GitLab 中为 CI_COMMIT_SHA）
+    #[arg(long)]
+    pub head: Option<String>,
+    /// 达到该严重级别的问题使流水线失败，覆盖 AI_CHECK_CI_FAIL_ON
+    #[arg(long, value_parser = ["low", "medium", "high", "none"])]
+    pub fail_on: Option<String>,
+    /// 写入 GitLab Code Quality 报告的路径，GitLab CI 中默认为 gl-code-quality-report.json
+    #[arg(long)]
+    pub code_quality: Option<PathBuf>,
+    /// 把结果发表为 PR 评论：一条汇总评论加上指向新增行的行内评论
+    #[arg(long)]
+    pub comment: bool,
+    /// 配合 --comment，PR/MR 编号，默认从 CI 环境变量推断
+    #[arg(long, requires = "comment")]
+    pub pr: Option<u64>,
+    #[command(flatten)]
+    pub check: CheckArgs,
 }
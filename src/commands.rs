@@ -1,3 +1,4 @@
+use crate::i18n::Msg;
 use anyhow::{Context, Result};
 use colored::*;
 use std::env;
@@ -24,98 +25,127 @@ const BINARY_NAME: &str = "ai_git_pre_commit"; // Fallback name
 // 默认配置文件名
 const CONFIG_NAME: &str = ".env";
 
-pub async fn install() -> Result<()> {
-    println!("{}", "🚀 开始安装...".blue().bold());
+// 本工具会安装的钩子
+const HOOK_NAMES: &[&str] = &["pre-commit", "commit-msg", "prepare-commit-msg"];
+
+pub async fn install(commit_msg: bool, prepare_commit_msg: bool) -> Result<()> {
+    println!("{}", Msg::InstallStarted.text().blue().bold());
 
     // 1. 安装配置文件
     install_config().await?;
 
     // 2. 安装钩子
-    install_hook()?;
+    install_hook("pre-commit", "")?;
+    if commit_msg {
+        install_hook("commit-msg", r#" commit-msg "$1""#)?;
+    }
+    if prepare_commit_msg {
+        install_hook("prepare-commit-msg", r#" prepare-commit-msg "$@""#)?;
+    }
 
     // 3. 添加到 PATH 提示
     check_path();
 
-    println!("\n{}", "✅ 安装成功完成！".green().bold());
+    println!("\n{}", Msg::InstallDone.text().green().bold());
     Ok(())
 }
 
 pub fn uninstall() -> Result<()> {
-    let hook_path = Path::new(".git").join("hooks").join("pre-commit");
-    
-    if hook_path.exists() {
-        // 通过读取内容检查是否为我们的钩子
-        let content = fs::read_to_string(&hook_path).unwrap_or_default();
-        if content.contains("AI Git Pre-Commit Hook") {
-            fs::remove_file(&hook_path).context("移除 pre-commit 钩子失败")?;
-            println!("{}", "✅ pre-commit 钩子已移除。".green());
-        } else {
-            println!("{}", "⚠️  发现 pre-commit 钩子，但看起来不是我们的。跳过移除。".yellow());
+    for name in HOOK_NAMES {
+        let hook_path = Path::new(".git").join("hooks").join(name);
+
+        if hook_path.exists() {
+            // 通过读取内容检查是否为我们的钩子
+            let content = fs::read_to_string(&hook_path).unwrap_or_default();
+            if content.contains("AI Git Pre-Commit Hook") {
+                fs::remove_file(&hook_path)
+                    .with_context(|| Msg::HookRemoveFailed.fill(&[("name", name.to_string())]))?;
+                println!("{}", Msg::HookRemoved.fill(&[("name", name.to_string())]).green());
+            } else {
+                println!(
+                    "{}",
+                    Msg::HookNotOurs.fill(&[("name", name.to_string())]).yellow()
+                );
+            }
+        } else if *name == "pre-commit" {
+            println!("{}", Msg::NoPreCommitHook.text().blue());
         }
-    } else {
-        println!("{}", "ℹ️  未发现 pre-commit 钩子。".blue());
     }
     Ok(())
 }
 
 pub async fn update() -> Result<()> {
-    println!("{}", "🔄 正在检查更新...".blue().bold());
+    println!("{}", Msg::CheckingUpdates.text().blue().bold());
     
-    let current_exe = env::current_exe().context("获取当前可执行文件路径失败")?;
+    let current_exe = env::current_exe().context(Msg::ExePathFailed.text())?;
     let download_url = format!("{}/{}", BASE_URL, BINARY_NAME);
     
-    println!("正在从 {} 下载", download_url);
+    println!("{}", Msg::DownloadingFrom.fill(&[("url", download_url.clone())]));
 
     // 下载新二进制文件
-    let response = reqwest::get(&download_url).await.context("下载更新失败")?;
+    let response = reqwest::get(&download_url)
+        .await
+        .context(Msg::UpdateDownloadFailed.text())?;
     if !response.status().is_success() {
-        anyhow::bail!("下载更新失败: HTTP {}", response.status());
+        anyhow::bail!(Msg::UpdateHttpFailed.fill(&[("status", response.status().to_string())]));
     }
-    let new_bytes = response.bytes().await.context("读取更新数据失败")?;
+    let new_bytes = response
+        .bytes()
+        .await
+        .context(Msg::UpdateReadFailed.text())?;
 
     // 处理更新（重命名旧文件，写入新文件）
     update_binary(&current_exe, &new_bytes)?;
 
-    println!("{}", "✅ 更新成功完成！".green().bold());
-    println!("{}", "ℹ️  配置文件 (.env) 未更新。如果丢失，请使用 'install' 下载配置文件。".blue());
+    println!("{}", Msg::UpdateDone.text().green().bold());
+    println!("{}", Msg::UpdateConfigNote.text().blue());
     Ok(())
 }
 
 async fn install_config() -> Result<()> {
     let current_exe = env::current_exe()?;
-    let exe_dir = current_exe.parent().context("获取可执行文件目录失败")?;
+    let exe_dir = current_exe.parent().context(Msg::ExeDirFailed.text())?;
     let config_path = exe_dir.join(CONFIG_NAME);
 
     if !config_path.exists() {
-        println!("正在下载配置文件...");
+        println!("{}", Msg::DownloadingConfig);
         let config_url = format!("{}/{}", BASE_URL, CONFIG_NAME);
         
-        let response = reqwest::get(&config_url).await.context("下载配置失败")?;
+        let response = reqwest::get(&config_url)
+            .await
+            .context(Msg::ConfigDownloadError.text())?;
         if response.status().is_success() {
             let content = response.bytes().await?;
-            fs::write(&config_path, content).context("写入配置文件失败")?;
-            println!("✅ 配置已安装到 {:?}", config_path);
+            fs::write(&config_path, content).context(Msg::ConfigWriteFailed.text())?;
+            println!(
+                "{}",
+                Msg::ConfigInstalled.fill(&[("path", format!("{:?}", config_path))])
+            );
         } else {
-            println!("{}", "❌ 下载配置文件失败。".red());
+            println!("{}", Msg::ConfigDownloadFailed.text().red());
         }
     } else {
-        println!("✅ 配置文件已存在于 {:?}", config_path);
+        println!(
+            "{}",
+            Msg::ConfigExists.fill(&[("path", format!("{:?}", config_path))])
+        );
     }
     Ok(())
 }
 
-fn install_hook() -> Result<()> {
+/// 在 `.git/hooks/<name>` 写入调用本程序的脚本，`args` 会原样追加在可执行文件路径之后。
+fn install_hook(name: &str, args: &str) -> Result<()> {
     if !Path::new(".git").exists() {
-        println!("{}", "⚠️  当前目录不是 git 仓库。跳过钩子安装。".yellow());
+        println!("{}", Msg::NotGitRepo.text().yellow());
         return Ok(());
     }
 
     let hooks_dir = Path::new(".git").join("hooks");
     if !hooks_dir.exists() {
-        fs::create_dir_all(&hooks_dir).context("创建 hooks 目录失败")?;
+        fs::create_dir_all(&hooks_dir).context(Msg::HooksDirFailed.text())?;
     }
 
-    let hook_path = hooks_dir.join("pre-commit");
+    let hook_path = hooks_dir.join(name);
     let current_exe = env::current_exe()?;
     let exe_dir = current_exe.parent().unwrap(); // Safe unwrap
 
@@ -136,26 +166,28 @@ fn install_hook() -> Result<()> {
     // 我们使用二进制文件的绝对路径并设置 AI_GIT_ENV_DIR
     let hook_content = format!(
 r#"#!/bin/sh
-# AI Git Pre-Commit Hook
+# AI Git Pre-Commit Hook ({})
 # Executing binary from: {}
 
 # Set environment to look for .env file in binary directory
 if [ -f "{}" ]; then
     export AI_GIT_ENV_DIR="{}"
-    "{}"
+    "{}"{}
 else
     echo "Error: AI Check binary not found at {}"
     exit 1
 fi
 "#,
+        name,
         current_exe_str,
         current_exe_str,
         exe_dir_str,
         current_exe_str,
+        args,
         current_exe_str
     );
 
-    fs::write(&hook_path, hook_content).context("写入钩子文件失败")?;
+    fs::write(&hook_path, hook_content).context(Msg::HookWriteFailed.text())?;
 
     #[cfg(unix)]
     {
@@ -165,7 +197,13 @@ fi
         fs::set_permissions(&hook_path, perms)?;
     }
 
-    println!("✅ Pre-commit 钩子已安装到 {:?}", hook_path);
+    println!(
+        "{}",
+        Msg::HookInstalled.fill(&[
+            ("name", name.to_string()),
+            ("path", format!("{:?}", hook_path)),
+        ])
+    );
     Ok(())
 }
 
@@ -177,9 +215,9 @@ fn check_path() {
                 // 简单检查目录是否在 PATH 中（Windows 不区分大小写？）
                 // 为简单起见，我们只做包含检查。
                 if !path_var.contains(&*parent_str) {
-                    println!("\n{}", "⚠️  警告：二进制目录不在您的 PATH 中。".yellow());
-                    println!("   请将 '{}' 添加到您的 PATH 环境变量中", parent_str);
-                    println!("   以便您可以从任何地方运行 'ai_git_pre_commit'。");
+                    println!("\n{}", Msg::PathWarning.text().yellow());
+                    println!("{}", Msg::PathAdd.fill(&[("dir", parent_str.to_string())]));
+                    println!("{}", Msg::PathReason);
                 }
             }
         }
@@ -198,7 +236,7 @@ fn update_binary(current_path: &PathBuf, new_bytes: &[u8]) -> Result<()> {
     }
 
     // 将当前文件重命名为 old
-    fs::rename(current_path, &old_path).context("Failed to rename current binary")?;
+    fs::rename(current_path, &old_path).context(Msg::BinaryRenameFailed.text())?;
     
     // 写入新二进制文件
     match fs::write(current_path, new_bytes) {
@@ -216,7 +254,7 @@ fn update_binary(current_path: &PathBuf, new_bytes: &[u8]) -> Result<()> {
         Err(e) => {
             // Rollback
             let _ = fs::rename(&old_path, current_path);
-            Err(anyhow::anyhow!("Failed to write new binary: {}", e))
+            Err(anyhow::anyhow!(Msg::BinaryWriteFailed.fill(&[("error", e.to_string())])))
         }
     }
 }
@@ -0,0 +1,140 @@
+use crate::ai::{self, Issue};
+use crate::config::Config;
+use crate::i18n::Msg;
+use crate::{git, prompts, report, usage};
+use anyhow::{Context, Result};
+use colored::*;
+use std::fs;
+use std::path::Path;
+use std::process::exit;
+
+/// `git commit -v` 在提交信息文件中插入的剪切线，之后的内容不属于提交信息。
+const SCISSORS: &str = "------------------------ >8 ------------------------";
+
+/// commit-msg 钩子入口：审查提交信息，发现高严重性问题时以状态码 1 阻止提交。
+pub async fn run(config: &Config, file: &Path) -> Result<()> {
+    println!("{}", Msg::CommitMsgCheckStarted.text().blue().bold());
+
+    let raw = fs::read_to_string(file)
+        .with_context(|| Msg::CommitMsgReadFailed.fill(&[("path", format!("{:?}", file))]))?;
+    let message = strip_comments(&raw);
+
+    if message.trim().is_empty() {
+        // 空提交信息会被 git 自己拒绝
+        exit(0);
+    }
+
+    if let Ok(git::RepoState::Merge) = git::repo_state(config) {
+        println!("{}", Msg::CommitMsgMergeSkipped.text().yellow());
+        exit(0);
+    }
+
+    let mut local_issues = check_subject_length(config, &message);
+    local_issues.extend(check_ticket(config, &message));
+
+    let summary = git::get_staged_summary(config).unwrap_or_default();
+    let system_prompt = prompts::generate_commit_msg_prompt(config);
+
+    let mut result =
+        match ai::call_commit_msg_check(config, system_prompt, &message, &summary).await {
+            Ok(res) => res,
+            Err(e) => {
+                for entry in ai::spent_usage(&e) {
+                    usage::record(config, "commit-msg", entry);
+                }
+                eprintln!("{} {}", Msg::AiCheckFailed.text().red().bold(), e);
+                exit(1);
+            }
+        };
+
+    // 本地规则的结果不依赖模型，直接并入并决定结论
+    result.prepend_local_issues(local_issues);
+
+    report::print_result(config, &result);
+    for entry in &result.usage {
+        usage::record(config, "commit-msg", entry);
+    }
+
+    if result.is_approved() {
+        println!("{}", Msg::CommitMsgApproved.text().green().bold());
+        exit(0);
+    } else {
+        println!("{}", Msg::CommitMsgRejected.text().red().bold());
+        exit(1);
+    }
+}
+
+/// 去掉 git 模板中的注释行以及 `-v` 剪切线之后的 diff。
+fn strip_comments(raw: &str) -> String {
+    raw.lines()
+        .take_while(|line| !line.contains(SCISSORS))
+        .filter(|line| !line.starts_with('#'))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// 标题长度是确定性规则，本地检查即可，不必交给模型。
+fn check_subject_length(config: &Config, message: &str) -> Vec<Issue> {
+    let subject = message.lines().next().unwrap_or_default();
+    let len = subject.chars().count();
+
+    if len <= config.commit_max_subject {
+        return Vec::new();
+    }
+
+    vec![Issue {
+        severity: "high".to_string(),
+        perspective: "commit_message".to_string(),
+        description: Msg::SubjectTooLong.fill(&[
+            ("len", len.to_string()),
+            ("max", config.commit_max_subject.to_string()),
+        ]),
+        suggestion: Msg::SubjectTooLongFix.text().to_string(),
+        location: "COMMIT_EDITMSG:1".to_string(),
+        patch: None,
+        models: Vec::new(),
+    }]
+}
+
+/// `AI_CHECK_COMMIT_REQUIRE_TICKET` 开启时，提交信息中必须有匹配 `AI_CHECK_COMMIT_TICKET_PATTERN` 的单号。
+/// 模型无法可靠地按正则判断，因此只在本地检查。
+fn check_ticket(config: &Config, message: &str) -> Vec<Issue> {
+    if !config.commit_require_ticket || config.commit_ticket_pattern.is_match(message) {
+        return Vec::new();
+    }
+
+    vec![Issue {
+        severity: "high".to_string(),
+        perspective: "commit_message".to_string(),
+        description: Msg::TicketMissing.fill(&[(
+            "pattern",
+            config.commit_ticket_pattern.as_str().to_string(),
+        )]),
+        suggestion: Msg::TicketMissingFix.text().to_string(),
+        location: "COMMIT_EDITMSG:1".to_string(),
+        patch: None,
+        models: Vec::new(),
+    }]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ticket_is_checked_with_the_configured_regex() {
+        let mut config = Config::load().unwrap();
+        config.commit_require_ticket = true;
+        assert!(check_ticket(&config, "feat: add login (PROJ-123)").is_empty());
+        assert_eq!(check_ticket(&config, "feat: add login").len(), 1);
+
+        config.commit_ticket_pattern = regex::Regex::new(r"#\d+").unwrap();
+        assert!(check_ticket(&config, "fix: crash\n\nCloses #42").is_empty());
+        assert_eq!(check_ticket(&config, "fix: crash (PROJ-123)").len(), 1);
+
+        config.commit_require_ticket = false;
+        assert!(check_ticket(&config, "fix: crash").is_empty());
+    }
+}
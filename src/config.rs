@@ -1,22 +1,178 @@
+use crate::i18n::{self, Locale, Msg};
 use anyhow::Result;
+use colored::*;
 use dotenv::dotenv;
+use regex::Regex;
 use std::env;
 
+/// 处于 rebase/cherry-pick/revert 等多步操作时的审查策略。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewPolicy {
+    /// 直接跳过检查
+    Skip,
+    /// 与普通提交一样审查全部暂存变更
+    Review,
+    /// 只审查手工解决冲突的部分
+    Conflicts,
+}
+
+impl ReviewPolicy {
+    /// 未设置或为空时使用 `default`；拼错的取值报错，而不是悄悄跳过检查。
+    fn from_env(key: &str, default: ReviewPolicy) -> Result<ReviewPolicy> {
+        let value = env::var(key).unwrap_or_default().trim().to_lowercase();
+        match value.as_str() {
+            "" => Ok(default),
+            "skip" => Ok(ReviewPolicy::Skip),
+            "review" => Ok(ReviewPolicy::Review),
+            "conflicts" => Ok(ReviewPolicy::Conflicts),
+            _ => Err(anyhow::anyhow!(Msg::PolicyInvalid.fill(&[
+                ("var", key.to_string()),
+                ("value", value.clone()),
+            ]))),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Config {
     pub api_key: String,
     pub model: String,
     pub base_url: String,
-    pub max_chunk_size: usize,
+    pub max_chunk_tokens: usize,
+    pub context_window: usize,
+    pub reserved_output_tokens: usize,
+    pub prompt_lang: Locale,
     pub language: String,
     pub check_security: bool,
     pub check_performance: bool,
     pub check_style: bool,
     pub check_sql: bool,
     pub enabled_extensions: Vec<String>,
+    pub exclude_patterns: Vec<String>,
+    pub git_backend: String,
+    pub on_merge: ReviewPolicy,
+    pub on_rebase: ReviewPolicy,
+    pub on_cherry_pick: ReviewPolicy,
+    pub on_revert: ReviewPolicy,
+    pub commit_convention: String,
+    pub commit_require_ticket: bool,
+    pub commit_ticket_pattern: Regex,
+    pub commit_language: String,
+    pub commit_max_subject: usize,
+    pub message_template: String,
+    pub stream: bool,
+    pub response_format: String,
+    pub repair_attempts: u32,
+    pub prices: Vec<(String, f64, f64)>,
+    pub track_usage: bool,
+    pub redact_secrets: bool,
+    pub local_rules: bool,
+    pub disabled_rules: Vec<String>,
+    pub max_file_lines: usize,
+    pub temperature: Option<f32>,
+    pub top_p: Option<f32>,
+    pub max_tokens: Option<u32>,
+    pub seed: Option<u64>,
+    pub extra_body: serde_json::Map<String, serde_json::Value>,
+    pub fallbacks: Vec<Endpoint>,
+    pub timeout_secs: u64,
+    pub consensus_models: Vec<Endpoint>,
+    pub consensus_quorum: usize,
+    pub consensus_verdict: String,
+    pub triage_model: Option<Endpoint>,
+    pub deep_model: Option<Endpoint>,
+    pub triage_escalate: String,
+    pub interactive: bool,
+    pub baseline_file: String,
+    pub ci_fail_on: String,
+    pub forge: String,
+    pub forge_api: Option<String>,
+    pub forge_token: Option<String>,
+    pub context_file: String,
+    pub context_max_tokens: usize,
+    pub system_template: String,
+    pub user_template: String,
+    pub ci_trust_head: bool,
+    pub trusted_rev: Option<String>,
+}
+
+/// 一个模型调用端点：模型名、接口地址和 API Key。
+#[derive(Debug, Clone)]
+pub struct Endpoint {
+    pub model: String,
+    pub base_url: String,
+    pub api_key: String,
+}
+
+impl Endpoint {
+    /// 解析环境变量 `var` 中逗号分隔的端点列表。
+    fn parse_list(var: &str, base_url: &str, api_key: &str) -> Result<Vec<Endpoint>> {
+        env::var(var)
+            .unwrap_or_default()
+            .split(',')
+            .filter(|entry| !entry.trim().is_empty())
+            .map(|entry| Endpoint::parse(var, entry, base_url, api_key))
+            .collect()
+    }
+
+    /// 解析 `model[@base_url][#API_KEY_ENV]`，省略的部分沿用主模型的配置。
+    fn parse(var: &str, entry: &str, base_url: &str, api_key: &str) -> Result<Endpoint> {
+        let (entry, key_env) = match entry.split_once('#') {
+            Some((entry, key_env)) => (entry, Some(key_env.trim())),
+            None => (entry, None),
+        };
+        let (model, url) = match entry.split_once('@') {
+            Some((model, url)) => (model.trim(), url.trim()),
+            None => (entry.trim(), base_url),
+        };
+        let api_key = match key_env {
+            Some(name) => env::var(name).map_err(|_| {
+                anyhow::anyhow!(Msg::EndpointKeyMissing
+                    .fill(&[("var", var.to_string()), ("name", name.to_string())]))
+            })?,
+            None => api_key.to_string(),
+        };
+
+        Ok(Endpoint {
+            model: model.to_string(),
+            base_url: url.to_string(),
+            api_key,
+        })
+    }
 }
 
 impl Config {
+    /// 按尝试顺序返回所有端点：主模型在前，随后是备用模型。
+    pub fn endpoints(&self) -> Vec<Endpoint> {
+        let primary = Endpoint {
+            model: self.model.clone(),
+            base_url: self.base_url.clone(),
+            api_key: self.api_key.clone(),
+        };
+        std::iter::once(primary)
+            .chain(self.fallbacks.iter().cloned())
+            .collect()
+    }
+
+    /// 参与共识审查的端点：主模型加上 `AI_CHECK_CONSENSUS_MODELS`。
+    pub fn consensus_endpoints(&self) -> Vec<Endpoint> {
+        self.endpoints()
+            .into_iter()
+            .take(1)
+            .chain(self.consensus_models.iter().cloned())
+            .collect()
+    }
+
+    /// 改用指定端点的配置副本，其余设置保持不变。
+    pub fn with_endpoint(&self, endpoint: &Endpoint) -> Config {
+        Config {
+            model: endpoint.model.clone(),
+            base_url: endpoint.base_url.clone(),
+            api_key: endpoint.api_key.clone(),
+            ..self.clone()
+        }
+    }
+
     pub fn load() -> Result<Self> {
         let mut loaded = false;
 
@@ -25,7 +181,7 @@ impl Config {
             let env_path = std::path::Path::new(&env_dir).join(".env");
             if env_path.exists() {
                 dotenv::from_path(&env_path).ok();
-                println!("📄 Loaded configuration from: {:?}", env_path);
+                println!("{}", Msg::ConfigLoaded.fill(&[("path", format!("{:?}", env_path))]));
                 loaded = true;
             }
         }
@@ -37,7 +193,10 @@ impl Config {
                     let env_path = exe_dir.join(".env");
                     if env_path.exists() {
                         dotenv::from_path(&env_path).ok();
-                        println!("📄 Loaded configuration from: {:?}", env_path);
+                        println!(
+                            "{}",
+                            Msg::ConfigLoaded.fill(&[("path", format!("{:?}", env_path))])
+                        );
                         loaded = true;
                     }
                 }
@@ -49,7 +208,7 @@ impl Config {
              // dotenv() 会搜索父目录，所以我们不能确定确切的路径。
              // 但是为了简单起见，我们可以检查当前目录是否存在 .env 文件。
              if std::path::Path::new(".env").exists() {
-                 println!("📄 Loaded configuration from current directory (.env)");
+                 println!("{}", Msg::ConfigLoadedCwd);
              }
              dotenv().ok();
         }
@@ -61,12 +220,56 @@ impl Config {
         let base_url = env::var("AI_CHECK_BASE_URL")
             .unwrap_or_else(|_| "https://api.deepseek.com/v1".to_string());
 
-        let max_chunk_size = env::var("AI_CHECK_MAX_CHUNK_SIZE")
-            .unwrap_or_else(|_| "4000".to_string())
+        // 单个 diff 块的 Token 上限，0 表示根据模型上下文窗口自动计算
+        let max_chunk_tokens = match (
+            env::var("AI_CHECK_MAX_CHUNK_TOKENS"),
+            env::var("AI_CHECK_MAX_CHUNK_SIZE"),
+        ) {
+            (Ok(tokens), legacy) => {
+                if legacy.is_ok() {
+                    eprintln!("{} {}", Msg::Warning.text().yellow(), Msg::ChunkSizeIgnored);
+                }
+                tokens.trim().parse().unwrap_or(0)
+            }
+            // 旧版本按字符数切分，换算为 Token 继续生效，避免升级后块大小悄悄改变
+            (Err(_), Ok(chars)) => {
+                let tokens = chars.trim().parse::<usize>().map_or(0, |chars| chars.div_ceil(4));
+                eprintln!(
+                    "{} {}",
+                    Msg::Warning.text().yellow(),
+                    Msg::ChunkSizeDeprecated.fill(&[("tokens", tokens.to_string())])
+                );
+                tokens
+            }
+            (Err(_), Err(_)) => 0,
+        };
+
+        // 覆盖内置的模型上下文窗口大小，0 表示使用内置表
+        let context_window = env::var("AI_CHECK_CONTEXT_WINDOW")
+            .unwrap_or_else(|_| "0".to_string())
             .parse()
-            .unwrap_or(4000);
+            .unwrap_or(0);
+
+        // 为模型输出预留的 Token 数
+        let reserved_output_tokens = env::var("AI_CHECK_RESERVED_OUTPUT_TOKENS")
+            .unwrap_or_else(|_| "4096".to_string())
+            .parse()
+            .unwrap_or(4096);
+
+        // 界面语言 (auto/zh-CN/en)，auto 按 LC_ALL/LC_MESSAGES/LANG 判断
+        let ui_lang = Locale::from_env();
+        i18n::set(ui_lang);
 
-        let language = env::var("AI_CHECK_LANGUAGE").unwrap_or_else(|_| "chinese".to_string());
+        // 内置提示词模板的语言，只跟随显式设置的 AI_CHECK_UI_LANG，默认中文；
+        // 系统语言环境不改变发给模型的内容
+        let prompt_lang = env::var("AI_CHECK_UI_LANG")
+            .ok()
+            .and_then(|v| Locale::parse(&v))
+            .unwrap_or(Locale::ZhCn);
+
+        // 模型描述问题的语言，默认与提示词模板的语言一致
+        let language = env::var("AI_CHECK_LANGUAGE")
+            .unwrap_or_else(|_| prompt_lang.default_language().to_string());
 
         let check_security = env::var("AI_CHECK_SECURITY")
             .unwrap_or_else(|_| "true".to_string())
@@ -96,17 +299,298 @@ impl Config {
             .map(|s| s.trim().to_string())
             .collect();
 
+        // 不审查的文件或目录，逗号分隔的通配符
+        let exclude_patterns = env::var("AI_CHECK_EXCLUDE")
+            .unwrap_or_default()
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        let git_backend = env::var("AI_CHECK_GIT_BACKEND")
+            .unwrap_or_else(|_| "auto".to_string())
+            .trim()
+            .to_lowercase();
+
+        let on_merge = ReviewPolicy::from_env("AI_CHECK_ON_MERGE", ReviewPolicy::Skip)?;
+        let on_rebase = ReviewPolicy::from_env("AI_CHECK_ON_REBASE", ReviewPolicy::Skip)?;
+        let on_cherry_pick =
+            ReviewPolicy::from_env("AI_CHECK_ON_CHERRY_PICK", ReviewPolicy::Skip)?;
+        let on_revert = ReviewPolicy::from_env("AI_CHECK_ON_REVERT", ReviewPolicy::Skip)?;
+
+        // 提交信息审查（commit-msg 钩子）
+        let commit_convention = env::var("AI_CHECK_COMMIT_CONVENTION")
+            .unwrap_or_else(|_| "conventional".to_string())
+            .trim()
+            .to_lowercase();
+
+        let commit_require_ticket = env::var("AI_CHECK_COMMIT_REQUIRE_TICKET")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .unwrap_or(false);
+
+        // 需求/缺陷单号的正则表达式，本地检查提交信息中是否包含
+        let commit_ticket_pattern = env::var("AI_CHECK_COMMIT_TICKET_PATTERN")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .unwrap_or_else(|| r"\b[A-Z][A-Z0-9]+-\d+\b".to_string());
+        let commit_ticket_pattern = Regex::new(commit_ticket_pattern.trim()).map_err(|e| {
+            anyhow::anyhow!(Msg::TicketPatternInvalid.fill(&[("error", e.to_string())]))
+        })?;
+
+        let commit_language = env::var("AI_CHECK_COMMIT_LANGUAGE").unwrap_or_default();
+
+        let commit_max_subject = env::var("AI_CHECK_COMMIT_MAX_SUBJECT")
+            .unwrap_or_else(|_| "72".to_string())
+            .parse()
+            .unwrap_or(72);
+
+        // 生成提交信息时遵循的模板（prepare-commit-msg 钩子 / suggest-message）
+        let message_template = env::var("AI_CHECK_MESSAGE_TEMPLATE")
+            .unwrap_or_else(|_| "<type>(<scope>): <subject>\n\n<body>".to_string());
+
+        let stream = env::var("AI_CHECK_STREAM")
+            .unwrap_or_else(|_| "true".to_string())
+            .parse()
+            .unwrap_or(true);
+
+        let response_format = env::var("AI_CHECK_RESPONSE_FORMAT")
+            .unwrap_or_else(|_| "auto".to_string())
+            .trim()
+            .to_lowercase();
+
+        let repair_attempts = env::var("AI_CHECK_REPAIR_ATTEMPTS")
+            .unwrap_or_else(|_| "1".to_string())
+            .parse()
+            .unwrap_or(1);
+
+        // 模型价格（美元 / 百万 Token），格式: model=输入价格/输出价格,...
+        let prices = env::var("AI_CHECK_PRICES")
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|entry| {
+                let (model, price) = entry.split_once('=')?;
+                let (input, output) = price.split_once('/')?;
+                Some((
+                    model.trim().to_string(),
+                    input.trim().parse().ok()?,
+                    output.trim().parse().ok()?,
+                ))
+            })
+            .collect();
+
+        let track_usage = env::var("AI_CHECK_TRACK_USAGE")
+            .unwrap_or_else(|_| "true".to_string())
+            .parse()
+            .unwrap_or(true);
+
+        let redact_secrets = env::var("AI_CHECK_SECRETS")
+            .unwrap_or_else(|_| "true".to_string())
+            .parse()
+            .unwrap_or(true);
+
+        let local_rules = env::var("AI_CHECK_LOCAL_RULES")
+            .unwrap_or_else(|_| "true".to_string())
+            .parse()
+            .unwrap_or(true);
+
+        let disabled_rules = env::var("AI_CHECK_DISABLED_RULES")
+            .unwrap_or_default()
+            .split(',')
+            .map(|s| s.trim().to_lowercase())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        let max_file_lines = env::var("AI_CHECK_MAX_FILE_LINES")
+            .unwrap_or_else(|_| "1000".to_string())
+            .parse()
+            .unwrap_or(1000);
+
+        // 采样参数：未设置时不发送，由服务商使用默认值
+        let temperature = env::var("AI_CHECK_TEMPERATURE")
+            .ok()
+            .and_then(|v| v.trim().parse().ok());
+
+        let top_p = env::var("AI_CHECK_TOP_P")
+            .ok()
+            .and_then(|v| v.trim().parse().ok());
+
+        let max_tokens = env::var("AI_CHECK_MAX_TOKENS")
+            .ok()
+            .and_then(|v| v.trim().parse().ok());
+
+        let seed = env::var("AI_CHECK_SEED")
+            .ok()
+            .and_then(|v| v.trim().parse().ok());
+
+        // 原样合并进请求体的额外字段（JSON 对象），用于服务商特有的参数
+        let extra_body = match env::var("AI_CHECK_EXTRA_BODY") {
+            Ok(raw) if !raw.trim().is_empty() => serde_json::from_str(&raw)
+                .map_err(|e| anyhow::anyhow!(Msg::ExtraBodyInvalid.fill(&[("error", e.to_string())])))?,
+            _ => serde_json::Map::new(),
+        };
+
+        // 主模型失败（网络错误、5xx、超时或输出无法解析）时按顺序尝试的备用模型
+        let fallbacks = Endpoint::parse_list("AI_CHECK_FALLBACK_MODELS", &base_url, &api_key)?;
+
+        // 多模型共识审查：与主模型一起审查同一块 diff 的其他模型
+        let consensus_models =
+            Endpoint::parse_list("AI_CHECK_CONSENSUS_MODELS", &base_url, &api_key)?;
+
+        // 一个问题被判为 high 所需的模型数，0 表示由投票方式决定
+        let consensus_quorum = env::var("AI_CHECK_CONSENSUS_QUORUM")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse()
+            .unwrap_or(0);
+
+        // 投票方式：majority（过半模型认定为 high）或 unanimous（全部通过，任一模型认定即为 high）
+        let consensus_verdict = env::var("AI_CHECK_CONSENSUS_VERDICT")
+            .unwrap_or_else(|_| "majority".to_string())
+            .trim()
+            .to_lowercase();
+
+        // 单次请求的超时时间（秒），0 表示不限制
+        let timeout_secs = env::var("AI_CHECK_TIMEOUT")
+            .unwrap_or_else(|_| "300".to_string())
+            .parse()
+            .unwrap_or(300);
+
+        // 两阶段审查：设置了深度审查模型后，先由分流模型（默认主模型）判断每块 diff 的风险
+        let deep_model = Endpoint::parse_list("AI_CHECK_DEEP_MODEL", &base_url, &api_key)?
+            .into_iter()
+            .next();
+        let triage_model = Endpoint::parse_list("AI_CHECK_TRIAGE_MODEL", &base_url, &api_key)?
+            .into_iter()
+            .next();
+
+        // 风险达到该级别（low/medium/high）的块升级到深度审查模型
+        let triage_escalate = env::var("AI_CHECK_TRIAGE_ESCALATE")
+            .unwrap_or_else(|_| "medium".to_string())
+            .trim()
+            .to_lowercase();
+
+        // 提交被拒绝且连接终端时，是否逐条询问如何处理
+        let interactive = env::var("AI_CHECK_INTERACTIVE")
+            .unwrap_or_else(|_| "true".to_string())
+            .parse()
+            .unwrap_or(true);
+
+        // 误报基线文件，相对于仓库根目录
+        let baseline_file = env::var("AI_CHECK_BASELINE")
+            .unwrap_or_else(|_| ".aicheck/baseline.json".to_string());
+
+        // CI 模式下达到该严重级别（low/medium/high）的问题使流水线失败，none 表示只报告
+        let ci_fail_on = env::var("AI_CHECK_CI_FAIL_ON")
+            .unwrap_or_else(|_| "high".to_string())
+            .trim()
+            .to_lowercase();
+
+        // 在 PR 中发表评论的代码托管平台 (auto/github/gitlab/gitea)，auto 根据 CI 环境判断
+        let forge = env::var("AI_CHECK_FORGE")
+            .unwrap_or_else(|_| "auto".to_string())
+            .trim()
+            .to_lowercase();
+
+        // 平台 API 地址（自建实例），留空时使用 CI 提供的地址或公共服务的默认地址
+        let forge_api = env::var("AI_CHECK_FORGE_API")
+            .ok()
+            .map(|url| url.trim().trim_end_matches('/').to_string())
+            .filter(|url| !url.is_empty());
+
+        // 平台访问令牌，留空时使用 CI 中常见的 GITHUB_TOKEN / GITLAB_TOKEN / GITEA_TOKEN
+        let forge_token = env::var("AI_CHECK_FORGE_TOKEN")
+            .ok()
+            .filter(|token| !token.trim().is_empty());
+
+        // 项目上下文文件（相对仓库根目录或子目录），留空则不读取
+        let context_file = env::var("AI_CHECK_CONTEXT_FILE")
+            .unwrap_or_else(|_| ".aicheck/context.md".to_string())
+            .trim()
+            .to_string();
+
+        // 所有项目上下文加在一起的最大 Token 数，超出部分截断
+        let context_max_tokens = env::var("AI_CHECK_CONTEXT_MAX_TOKENS")
+            .unwrap_or_else(|_| "2000".to_string())
+            .parse()
+            .unwrap_or(2000);
+
+        // 审查提示词模板文件（相对仓库根目录），文件不存在时使用内置模板
+        let system_template = env::var("AI_CHECK_SYSTEM_TEMPLATE")
+            .unwrap_or_else(|_| ".aicheck/prompts/system.md".to_string())
+            .trim()
+            .to_string();
+        let user_template = env::var("AI_CHECK_USER_TEMPLATE")
+            .unwrap_or_else(|_| ".aicheck/prompts/user.md".to_string())
+            .trim()
+            .to_string();
+
+        // CI 中是否使用 PR 自己修改过的提示词模板、项目上下文和误报基线，默认从目标分支读取
+        let ci_trust_head = env::var("AI_CHECK_CI_TRUST_HEAD")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .unwrap_or(false);
+
         Ok(Config {
             api_key,
             model,
             base_url,
-            max_chunk_size,
+            max_chunk_tokens,
+            context_window,
+            reserved_output_tokens,
+            prompt_lang,
             language,
             check_security,
             check_performance,
             check_style,
             check_sql,
             enabled_extensions,
+            exclude_patterns,
+            git_backend,
+            on_merge,
+            on_rebase,
+            on_cherry_pick,
+            on_revert,
+            commit_convention,
+            commit_require_ticket,
+            commit_ticket_pattern,
+            commit_language,
+            commit_max_subject,
+            message_template,
+            stream,
+            response_format,
+            repair_attempts,
+            prices,
+            track_usage,
+            redact_secrets,
+            local_rules,
+            disabled_rules,
+            max_file_lines,
+            temperature,
+            top_p,
+            max_tokens,
+            seed,
+            extra_body,
+            fallbacks,
+            timeout_secs,
+            consensus_models,
+            consensus_quorum,
+            consensus_verdict,
+            triage_model,
+            deep_model,
+            triage_escalate,
+            interactive,
+            baseline_file,
+            ci_fail_on,
+            forge,
+            forge_api,
+            forge_token,
+            context_file,
+            context_max_tokens,
+            system_template,
+            user_template,
+            ci_trust_head,
+            // 由 ci 子命令设置，本地检查时读取工作区
+            trusted_rev: None,
         })
     }
 }
@@ -0,0 +1,285 @@
+use crate::ai::{self, AiCheckResult, Issue, ModelUsage};
+use crate::config::Config;
+use crate::diff;
+use crate::i18n::Msg;
+use crate::progress::Progress;
+use anyhow::Result;
+use colored::*;
+use futures_util::future::join_all;
+
+/// 把同一块 diff 同时交给多个模型审查，按投票决定问题的严重程度，再由合并后的问题决定结论。
+///
+/// 某个模型调用失败时只打印警告并不参与投票；全部失败才返回错误。
+pub async fn review(
+    config: &Config,
+    system_prompt: String,
+    user_content: String,
+) -> Result<AiCheckResult> {
+    let endpoints = config.consensus_endpoints();
+    // 所有模型共用一个进度行，避免多个 spinner 在同一行上互相覆盖
+    let progress =
+        Progress::start(&Msg::ProgressVoting.fill(&[("count", endpoints.len().to_string())]));
+    let replies = join_all(endpoints.iter().map(|endpoint| {
+        let endpoint_config = config.with_endpoint(endpoint);
+        let (system_prompt, user_content) = (system_prompt.clone(), user_content.clone());
+        let progress = &progress;
+        async move {
+            ai::request_check_once(
+                &endpoint_config,
+                system_prompt,
+                user_content,
+                Some(progress),
+            )
+            .await
+        }
+    }))
+    .await;
+    progress.finish();
+
+    let mut results = Vec::new();
+    let mut last_err: Option<anyhow::Error> = None;
+    // 调用失败的模型已经消耗的 Token（不含 last_err 自带的部分）
+    let mut spent = Vec::new();
+
+    for (endpoint, reply) in endpoints.iter().zip(replies) {
+        match reply {
+            Ok(result) => results.push(result),
+            Err(e) => {
+                eprintln!(
+                    "{} {}",
+                    Msg::Warning.text().yellow(),
+                    Msg::VoterFailed.fill(&[
+                        ("model", endpoint.model.clone()),
+                        ("error", format!("{:#}", e)),
+                    ])
+                );
+                if let Some(prev) = last_err.replace(e) {
+                    spent.extend(ai::spent_usage(&prev).iter().cloned());
+                }
+            }
+        }
+    }
+
+    if results.is_empty() {
+        let err = last_err.expect("至少请求过一个模型");
+        return Err(ai::FailedCheck::wrap(err, spent).into());
+    }
+    if let Some(err) = &last_err {
+        spent.extend(ai::spent_usage(err).iter().cloned());
+    }
+
+    let quorum = quorum(config, results.len());
+
+    let mut groups: Vec<Group> = Vec::new();
+    for result in &mut results {
+        let model = result.models.first().cloned().unwrap_or_default();
+        for issue in std::mem::take(&mut result.list) {
+            group_issue(&mut groups, model.clone(), issue);
+        }
+    }
+
+    let mut combined = results
+        .into_iter()
+        .reduce(AiCheckResult::merge)
+        .expect("results 非空");
+    combined.list = groups.into_iter().map(|g| g.resolve(quorum)).collect();
+    // 结论与合并后的问题保持一致：只要还有达到法定人数的 high 问题就拒绝
+    combined.result = if combined.list.iter().any(is_high) {
+        "NO"
+    } else {
+        "YES"
+    }
+    .to_string();
+    for entry in spent {
+        ModelUsage::accumulate(&mut combined.usage, entry);
+    }
+
+    Ok(combined)
+}
+
+/// 问题被判为 high 所需的模型数。
+///
+/// 未指定法定人数时由投票方式决定：majority 需要过半模型认定为 high，
+/// unanimous 要求所有模型都认可才通过，因此任一模型认定的 high 都保留。
+/// 部分模型失败时，法定人数不能超过实际参与投票的模型数。
+fn quorum(config: &Config, voters: usize) -> usize {
+    match (config.consensus_quorum, config.consensus_verdict.as_str()) {
+        (0, "unanimous") => 1,
+        (0, _) => voters / 2 + 1,
+        (n, _) => n.min(voters),
+    }
+}
+
+/// 把一个模型提出的问题归入已有的组，找不到相同的问题时新建一组。
+fn group_issue(groups: &mut Vec<Group>, model: String, issue: Issue) {
+    match groups.iter_mut().find(|g| g.matches(&issue)) {
+        Some(group) => group.add(model, issue),
+        None => groups.push(Group::new(model, issue)),
+    }
+}
+
+/// 合并问题时允许的行号偏差。
+const NEARBY_LINES: usize = 3;
+
+/// 不同模型针对同一位置、同一视角提出的问题。
+struct Group {
+    reports: Vec<(String, Issue)>,
+}
+
+impl Group {
+    fn new(model: String, issue: Issue) -> Self {
+        Group {
+            reports: vec![(model, issue)],
+        }
+    }
+
+    /// 同一视角、同一文件中相距不超过 `NEARBY_LINES` 行的问题视为同一个问题：
+    /// 不同模型对同一处代码给出的行号常常差一两行。位置无法解析时要求完全一致。
+    fn matches(&self, issue: &Issue) -> bool {
+        let first = &self.reports[0].1;
+        if normalize(&first.perspective) != normalize(&issue.perspective) {
+            return false;
+        }
+        match (
+            diff::parse_location(&first.location),
+            diff::parse_location(&issue.location),
+        ) {
+            (Some((a, line_a)), Some((b, line_b))) => {
+                normalize(a) == normalize(b) && line_a.abs_diff(line_b) <= NEARBY_LINES
+            }
+            _ => normalize(&first.location) == normalize(&issue.location),
+        }
+    }
+
+    fn add(&mut self, model: String, issue: Issue) {
+        self.reports.push((model, issue));
+    }
+
+    /// 合并为一个问题：只有达到法定人数的模型都认为是 high 时才保留 high，否则降为 medium。
+    fn resolve(self, quorum: usize) -> Issue {
+        let mut models: Vec<String> = Vec::new();
+        let mut high_votes: Vec<&str> = Vec::new();
+        for (model, issue) in &self.reports {
+            if !models.contains(model) {
+                models.push(model.clone());
+            }
+            if is_high(issue) && !high_votes.contains(&model.as_str()) {
+                high_votes.push(model);
+            }
+        }
+        let high = high_votes.len() >= quorum;
+
+        // 以最严重的那份描述为准
+        let (_, mut issue) = self
+            .reports
+            .into_iter()
+            .rev()
+            .max_by_key(|(_, issue)| rank(&issue.severity))
+            .expect("group 至少包含一个问题");
+        if is_high(&issue) && !high {
+            issue.severity = "medium".to_string();
+        }
+        issue.models = models;
+        issue
+    }
+}
+
+fn is_high(issue: &Issue) -> bool {
+    issue.severity.eq_ignore_ascii_case("high")
+}
+
+fn rank(severity: &str) -> u8 {
+    match severity.to_lowercase().as_str() {
+        "high" => 3,
+        "medium" => 2,
+        "low" => 1,
+        _ => 0,
+    }
+}
+
+fn normalize(s: &str) -> String {
+    s.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue(severity: &str, perspective: &str, location: &str) -> Issue {
+        Issue {
+            severity: severity.to_string(),
+            perspective: perspective.to_string(),
+            description: format!("{} at {}", severity, location),
+            suggestion: String::new(),
+            location: location.to_string(),
+            patch: None,
+            models: Vec::new(),
+        }
+    }
+
+    fn grouped(reports: Vec<(&str, Issue)>) -> Vec<Group> {
+        let mut groups = Vec::new();
+        for (model, issue) in reports {
+            group_issue(&mut groups, model.to_string(), issue);
+        }
+        groups
+    }
+
+    #[test]
+    fn nearby_lines_in_same_file_and_perspective_are_one_issue() {
+        let groups = grouped(vec![
+            ("a", issue("high", "security", "src/x.rs:10")),
+            ("b", issue("high", "Security ", "src/x.rs:12")),
+            ("c", issue("high", "security", "src/x.rs:20")),
+            ("c", issue("high", "general", "src/x.rs:10")),
+            ("b", issue("low", "security", "src/y.rs:10")),
+        ]);
+        let sizes: Vec<_> = groups.iter().map(|g| g.reports.len()).collect();
+        assert_eq!(sizes, [2, 1, 1, 1]);
+    }
+
+    #[test]
+    fn unparseable_locations_must_match_exactly() {
+        let groups = grouped(vec![
+            ("a", issue("medium", "general", "整个文件")),
+            ("b", issue("medium", "general", "整个文件")),
+            ("c", issue("medium", "general", "README")),
+        ]);
+        assert_eq!(groups.len(), 2);
+    }
+
+    #[test]
+    fn high_needs_quorum_votes_from_distinct_models() {
+        let reports = || {
+            grouped(vec![
+                ("a", issue("high", "security", "src/x.rs:10")),
+                ("a", issue("high", "security", "src/x.rs:11")),
+                ("b", issue("medium", "security", "src/x.rs:10")),
+            ])
+        };
+
+        let kept = reports().pop().unwrap().resolve(1);
+        assert_eq!(kept.severity, "high");
+        assert_eq!(kept.models, ["a", "b"]);
+
+        // 同一模型的两次报告只算一票
+        let downgraded = reports().pop().unwrap().resolve(2);
+        assert_eq!(downgraded.severity, "medium");
+        assert_eq!(downgraded.description, "high at src/x.rs:10");
+    }
+
+    #[test]
+    fn quorum_follows_verdict_and_voters() {
+        let mut config = Config::load().unwrap();
+        config.consensus_quorum = 0;
+        config.consensus_verdict = "majority".to_string();
+        assert_eq!(quorum(&config, 3), 2);
+        assert_eq!(quorum(&config, 4), 3);
+        config.consensus_verdict = "unanimous".to_string();
+        assert_eq!(quorum(&config, 3), 1);
+        // 指定的法定人数不超过实际投票的模型数
+        config.consensus_quorum = 3;
+        assert_eq!(quorum(&config, 2), 2);
+        assert_eq!(quorum(&config, 5), 3);
+    }
+}
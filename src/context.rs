@@ -0,0 +1,102 @@
+use crate::config::Config;
+use crate::i18n::{Locale, Msg};
+use crate::{diff, git, tokens};
+use colored::*;
+use std::collections::BTreeSet;
+
+/// 上下文超出预算被截断时附加的说明。
+fn truncated_note(config: &Config) -> &'static str {
+    match config.prompt_lang {
+        Locale::ZhCn => "\n[项目上下文过长，已截断]",
+        Locale::En => "\n[Project context truncated due to size limit]",
+    }
+}
+
+/// 一份项目上下文文件及其适用的目录。
+pub struct ProjectContext {
+    /// 适用的目录（相对仓库根目录，以 `/` 结尾），根目录为空字符串
+    pub scope: String,
+    /// 上下文文件路径（相对仓库根目录）
+    pub path: String,
+    pub content: String,
+}
+
+/// 读取与本次 diff 相关的项目上下文：仓库根目录下的 `AI_CHECK_CONTEXT_FILE`，
+/// 以及被修改文件所在各级目录下的同名文件（monorepo 中各子项目自己的约定）。
+///
+/// 结果按目录从浅到深排列，总长度不超过 `AI_CHECK_CONTEXT_MAX_TOKENS`，
+/// 超出时截断后面的文件，因此根目录的上下文总是优先保留。
+pub fn load(config: &Config, diff_text: &str) -> Vec<ProjectContext> {
+    if config.context_file.is_empty() || config.context_max_tokens == 0 {
+        return Vec::new();
+    }
+
+    // 有序集合保证父目录排在子目录之前
+    let mut scopes = BTreeSet::from([String::new()]);
+    for file in diff::files(diff_text) {
+        let mut dir = String::new();
+        if let Some((parents, _)) = file.rsplit_once('/') {
+            for part in parents.split('/') {
+                dir.push_str(part);
+                dir.push('/');
+                scopes.insert(dir.clone());
+            }
+        }
+    }
+
+    let mut budget = config.context_max_tokens;
+    let mut contexts = Vec::new();
+    for scope in scopes {
+        let path = format!("{}{}", scope, config.context_file);
+        // CI 中从目标分支读取，见 `git::read_repo_file`
+        let Ok(Some((content, _))) = git::read_repo_file(config, &path) else {
+            continue;
+        };
+        let content = content.trim();
+        if content.is_empty() {
+            continue;
+        }
+
+        let size = tokens::count(&config.model, content);
+        let content = if size <= budget {
+            budget -= size;
+            content.to_string()
+        } else {
+            // 剩余预算放不下任何内容时整个文件忽略，否则截断
+            let keep = budget.saturating_sub(tokens::count(&config.model, truncated_note(config)));
+            budget = 0;
+            if keep == 0 {
+                eprintln!(
+                    "{} {}",
+                    Msg::Warning.text().yellow(),
+                    Msg::ContextSkipped.fill(&[
+                        ("max", config.context_max_tokens.to_string()),
+                        ("path", path.to_string()),
+                    ])
+                );
+                continue;
+            }
+            eprintln!(
+                "{} {}",
+                Msg::Warning.text().yellow(),
+                Msg::ContextTruncated.fill(&[
+                    ("max", config.context_max_tokens.to_string()),
+                    ("path", path.to_string()),
+                ])
+            );
+            format!(
+                "{}{}",
+                tokens::truncate(&config.model, content, keep),
+                truncated_note(config)
+            )
+        };
+
+        contexts.push(ProjectContext {
+            scope,
+            path,
+            content,
+        });
+    }
+
+    contexts
+}
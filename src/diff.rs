@@ -0,0 +1,426 @@
+use std::collections::HashMap;
+
+/// diff 中新增的一行，以及它在新文件中的位置。
+#[derive(Debug, Clone)]
+pub struct AddedLine<'a> {
+    /// diff 文本中的行序号（从 0 开始），用于原地替换内容
+    pub index: usize,
+    /// 新文件路径（去掉 `b/` 前缀）
+    pub file: String,
+    /// 新文件中的行号（从 1 开始）
+    pub line_no: usize,
+    /// 去掉 `+` 前缀后的内容（不含换行）
+    pub content: &'a str,
+}
+
+/// diff 正文（hunk 中）的一行，包括新增、删除和上下文行。
+#[derive(Debug, Clone)]
+pub struct BodyLine<'a> {
+    /// diff 文本中的行序号（从 0 开始），用于原地替换内容
+    pub index: usize,
+    /// 新文件路径（去掉 `b/` 前缀）
+    pub file: String,
+    /// 行首的 `+`/`-`/空格（combined diff 中为两列）
+    pub prefix: &'a str,
+    /// 去掉前缀后的内容（不含换行）
+    pub content: &'a str,
+}
+
+/// diff 中一行的类别，由 `classify` 给出。
+enum Line<'a> {
+    /// 文件头（`diff --git`、`index`、`---` 等）；`+++` 行带上新文件路径（去掉 `b/` 前缀）
+    Header(Option<&'a str>),
+    /// `@@` 行
+    Hunk,
+    /// hunk 中的一行：前缀列和内容
+    Body(&'a str, &'a str),
+    /// `\ No newline at end of file`
+    Marker,
+}
+
+/// 逐行标注 diff（或 `--cc` combined diff）的类别。
+///
+/// hunk 中新增的 `++ x`、删除的 `-- x` 与文件头的形式相同，按 `@@` 行记录的行数判断：
+/// 行数用完之前都是正文，之后才是下一个文件的文件头。
+fn classify(diff: &str) -> Vec<(usize, &str, Line<'_>)> {
+    let mut out = Vec::new();
+    let mut in_hunks = false;
+    // combined diff 每行有“父提交数”个前缀列，普通 diff 为 1
+    let mut columns = 1usize;
+    // 当前 hunk 中新文件和各个原文件剩余的行数
+    let mut new_left = 0usize;
+    let mut old_left: Vec<usize> = Vec::new();
+
+    for (index, line) in diff.lines().enumerate() {
+        let counted = new_left > 0 || old_left.iter().any(|n| *n > 0);
+        let kind = if line.starts_with("diff ") {
+            in_hunks = false;
+            Line::Header(None)
+        } else if line.starts_with("@@") {
+            in_hunks = true;
+            new_left = new_range(line).map_or(0, |(_, count)| count);
+            old_left = old_ranges(line).map(|(_, count)| count).collect();
+            columns = old_left.len().max(1);
+            Line::Hunk
+        } else if line.starts_with('\\') {
+            Line::Marker
+        } else if !in_hunks || (!counted && (line.starts_with("+++ ") || line.starts_with("--- ")))
+        {
+            in_hunks = false;
+            Line::Header(
+                line.strip_prefix("+++ ")
+                    .map(|p| p.strip_prefix("b/").unwrap_or(p)),
+            )
+        } else {
+            let split = columns.min(line.len());
+            let (prefix, content) = if line.is_char_boundary(split) {
+                line.split_at(split)
+            } else {
+                (line, "")
+            };
+            // 第 i 列不是 `+` 的行存在于第 i 个原文件；任何一列都不是 `-` 的行存在于新文件
+            for (i, left) in old_left.iter_mut().enumerate() {
+                if prefix.as_bytes().get(i) != Some(&b'+') {
+                    *left = left.saturating_sub(1);
+                }
+            }
+            if !prefix.contains('-') {
+                new_left = new_left.saturating_sub(1);
+            }
+            Line::Body(prefix, content)
+        };
+        out.push((index, line, kind));
+    }
+
+    out
+}
+
+/// diff 正文中的所有行，用于在发送前处理 diff 的全部内容（如脱敏）。
+pub fn body_lines(diff: &str) -> Vec<BodyLine<'_>> {
+    let mut lines = Vec::new();
+    let mut file = "";
+    for (index, _, kind) in classify(diff) {
+        match kind {
+            Line::Header(Some(path)) => file = path,
+            Line::Body(prefix, content) => lines.push(BodyLine {
+                index,
+                file: file.to_string(),
+                prefix,
+                content,
+            }),
+            _ => {}
+        }
+    }
+    lines
+}
+
+/// 遍历 unified diff（或 `--cc` combined diff）中所有新增的行。
+pub fn added_lines(diff: &str) -> Vec<AddedLine<'_>> {
+    let mut lines = Vec::new();
+    let mut file = "";
+    let mut line_no = 0usize;
+
+    for (index, text, kind) in classify(diff) {
+        match kind {
+            Line::Header(Some(path)) => file = path,
+            Line::Hunk => line_no = new_start(text).unwrap_or(1),
+            Line::Body(prefix, content) => {
+                if prefix.contains('-') {
+                    // 删除的行不占用新文件的行号
+                    continue;
+                }
+                if prefix.contains('+') {
+                    lines.push(AddedLine {
+                        index,
+                        file: file.to_string(),
+                        line_no,
+                        content,
+                    });
+                }
+                line_no += 1;
+            }
+            _ => {}
+        }
+    }
+
+    lines
+}
+
+/// diff 中涉及的文件（新文件路径，去掉 `b/` 前缀），按出现顺序去重。
+pub fn files(diff: &str) -> Vec<String> {
+    let mut files: Vec<String> = Vec::new();
+    for (_, _, kind) in classify(diff) {
+        let Line::Header(Some(path)) = kind else {
+            continue;
+        };
+        if path == "/dev/null" {
+            continue;
+        }
+        if !files.iter().any(|f| f == path) {
+            files.push(path.to_string());
+        }
+    }
+    files
+}
+
+/// 文件头中 `---`、`+++` 行给出的路径（去掉 `a/`、`b/` 前缀），不包括 hunk 中形式相同的内容行。
+pub fn header_paths(diff: &str) -> Vec<&str> {
+    classify(diff)
+        .into_iter()
+        .filter(|(_, _, kind)| matches!(kind, Line::Header(_)))
+        .filter_map(|(_, text, _)| {
+            let path = text
+                .strip_prefix("--- ")
+                .or_else(|| text.strip_prefix("+++ "))?
+                .trim();
+            Some(
+                path.strip_prefix("a/")
+                    .or_else(|| path.strip_prefix("b/"))
+                    .unwrap_or(path),
+            )
+        })
+        .collect()
+}
+
+/// diff 中所有的 `@@` 行。
+pub fn hunk_headers(diff: &str) -> Vec<&str> {
+    classify(diff)
+        .into_iter()
+        .filter(|(_, _, kind)| matches!(kind, Line::Hunk))
+        .map(|(_, text, _)| text)
+        .collect()
+}
+
+/// 只保留 `keep` 返回 true 的文件的 diff 段，第一个 `diff ` 行之前的说明文字原样保留。
+pub fn retain_files(diff: &str, keep: impl Fn(&str) -> bool) -> String {
+    let mut out = String::with_capacity(diff.len());
+    let mut section = String::new();
+    let mut in_section = false;
+
+    let flush = |section: &mut String, out: &mut String| {
+        // 二进制文件等没有 `+++` 行时从 `diff --git a/x b/x` 中取路径
+        let file = files(section).into_iter().next().or_else(|| {
+            let header = section.lines().next()?;
+            Some(header.rsplit_once(" b/")?.1.to_string())
+        });
+        if file.is_some_and(|f| keep(&f)) {
+            out.push_str(section);
+        }
+        section.clear();
+    };
+
+    for line in diff.split_inclusive('\n') {
+        if line.starts_with("diff ") {
+            if in_section {
+                flush(&mut section, &mut out);
+            }
+            in_section = true;
+        }
+        if in_section {
+            section.push_str(line);
+        } else {
+            out.push_str(line);
+        }
+    }
+    if in_section {
+        flush(&mut section, &mut out);
+    }
+
+    out
+}
+
+/// 用 `replacements`（行序号 → 新的整行内容，不含换行）替换 diff 中对应的行。
+pub fn replace_lines(diff: &str, replacements: &HashMap<usize, String>) -> String {
+    let mut out = String::with_capacity(diff.len());
+    for (index, line) in diff.split_inclusive('\n').enumerate() {
+        match replacements.get(&index) {
+            Some(content) => {
+                out.push_str(content);
+                let body = line.trim_end_matches(['\r', '\n']);
+                out.push_str(&line[body.len()..]);
+            }
+            None => out.push_str(line),
+        }
+    }
+    out
+}
+
+/// 返回 diff 中包含新文件第 `line` 行的 hunk（含 `@@` 行），用于展示问题的上下文。
+pub fn hunk_at<'a>(diff: &'a str, file: &str, line: usize) -> Option<Vec<&'a str>> {
+    let mut current_file = "";
+    let mut hunk: Vec<&str> = Vec::new();
+    let mut range = (0usize, 0usize);
+
+    let contains = |range: (usize, usize)| {
+        // 纯删除的 hunk 新文件行数为 0，按删除位置所在的行处理
+        line >= range.0 && line < range.0 + range.1.max(1)
+    };
+
+    for (_, text, kind) in classify(diff) {
+        match kind {
+            Line::Header(_) | Line::Hunk => {
+                if !hunk.is_empty() && current_file == file && contains(range) {
+                    return Some(hunk);
+                }
+                hunk.clear();
+                match kind {
+                    Line::Header(Some(path)) => current_file = path,
+                    Line::Hunk => {
+                        range = new_range(text).unwrap_or_default();
+                        hunk.push(text);
+                    }
+                    _ => {}
+                }
+            }
+            Line::Body(..) | Line::Marker => {
+                if !hunk.is_empty() {
+                    hunk.push(text);
+                }
+            }
+        }
+    }
+
+    (!hunk.is_empty() && current_file == file && contains(range)).then_some(hunk)
+}
+
+/// 拆分 `path:line`（或 `path:12-15`）形式的问题位置，只取起始行号。
+pub fn parse_location(location: &str) -> Option<(&str, usize)> {
+    let (file, line) = location.trim().rsplit_once(':')?;
+    let digits: String = line.chars().take_while(|c| c.is_ascii_digit()).collect();
+    Some((file, digits.parse().ok()?))
+}
+
+/// 从 `@@ -a,b +c,d @@`（或 `@@@ -a,b -c,d +e,f @@@`）中取出新文件的起始行号。
+fn new_start(header: &str) -> Option<usize> {
+    new_range(header).map(|(start, _)| start)
+}
+
+/// `@@` 行中的行号范围，不包括结尾 `@@` 之后的函数名等上下文。
+fn header_ranges(header: &str) -> impl Iterator<Item = &str> {
+    header
+        .split_whitespace()
+        .skip(1)
+        .take_while(|part| !part.starts_with('@'))
+}
+
+/// 新文件的起始行号和行数（省略行数时为 1）。
+fn new_range(header: &str) -> Option<(usize, usize)> {
+    let range = header_ranges(header).find(|part| part.starts_with('+'))?;
+    parse_range(range)
+}
+
+/// 原文件的起始行号和行数（省略行数时为 1），用于判断补丁修改的是哪几行。
+pub fn old_range(header: &str) -> Option<(usize, usize)> {
+    old_ranges(header).next()
+}
+
+/// 各个原文件的行号范围，combined diff 中每个父提交一个。
+fn old_ranges(header: &str) -> impl Iterator<Item = (usize, usize)> + '_ {
+    header_ranges(header)
+        .filter(|part| part.starts_with('-'))
+        .filter_map(parse_range)
+}
+
+/// 解析 `+c,d` / `-a,b` 形式的行号范围。
+fn parse_range(range: &str) -> Option<(usize, usize)> {
+    let mut parts = range[1..].split(',');
+    let start = parts.next()?.parse().ok()?;
+    let count = match parts.next() {
+        Some(count) => count.parse().ok()?,
+        None => 1,
+    };
+    Some((start, count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn added_lines_track_new_line_numbers() {
+        let diff =
+            "diff --git a/a.rs b/a.rs\n--- a/a.rs\n+++ b/a.rs\n@@ -10,3 +10,3 @@ fn main() {\n \
+                    keep\n-old\n+new\n keep\n";
+        let lines = added_lines(diff);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].file, "a.rs");
+        assert_eq!(lines[0].line_no, 11);
+        assert_eq!(lines[0].content, "new");
+        assert_eq!(lines[0].index, 6);
+    }
+
+    #[test]
+    fn plus_plus_content_inside_hunk_is_not_a_header() {
+        // 新增的 `++ x` 行和删除的 `-- y` 行在 diff 中是 `+++ x` / `--- y`
+        let diff = "diff --git a/a.md b/a.md\n--- a/a.md\n+++ b/a.md\n@@ -1,2 +1,3 @@\n \
+                    a\n--- y\n+++ x\n+b\n\
+                    diff --git a/b.md b/b.md\n--- a/b.md\n+++ b/b.md\n@@ -1 +1 @@\n-c\n+d\n";
+        let lines = added_lines(diff);
+        let found: Vec<_> = lines
+            .iter()
+            .map(|l| (l.file.as_str(), l.line_no, l.content))
+            .collect();
+        assert_eq!(
+            found,
+            [("a.md", 2, "++ x"), ("a.md", 3, "b"), ("b.md", 1, "d")]
+        );
+        assert_eq!(files(diff), ["a.md", "b.md"]);
+    }
+
+    #[test]
+    fn headers_without_diff_line_follow_exhausted_hunk() {
+        let diff = "--- a/a.rs\n+++ b/a.rs\n@@ -1 +1 @@\n-a\n+b\n--- a/b.rs\n+++ b/b.rs\n@@ -5,0 +6 @@\n+c\n";
+        let found: Vec<_> = added_lines(diff)
+            .iter()
+            .map(|l| (l.file.clone(), l.line_no))
+            .collect();
+        assert_eq!(found, [("a.rs".to_string(), 1), ("b.rs".to_string(), 6)]);
+    }
+
+    #[test]
+    fn combined_diff_uses_two_prefix_columns() {
+        let diff =
+            "diff --cc a.rs\nindex 1,2..3\n--- a/a.rs\n+++ b/a.rs\n@@@ -1,2 -1,2 +1,3 @@@\n  \
+                    same\n- ours\n +theirs\n++merged\n";
+        let found: Vec<_> = added_lines(diff)
+            .iter()
+            .map(|l| (l.line_no, l.content))
+            .collect();
+        assert_eq!(found, [(2, "theirs"), (3, "merged")]);
+        let body: Vec<_> = body_lines(diff).iter().map(|l| l.prefix).collect();
+        assert_eq!(body, ["  ", "- ", " +", "++"]);
+    }
+
+    #[test]
+    fn body_lines_include_removed_and_context_lines() {
+        let diff = "diff --git a/a.rs b/a.rs\n--- a/a.rs\n+++ b/a.rs\n@@ -1,2 +1 @@\n a\n-b\n\\ No newline at end of file\n";
+        let body: Vec<_> = body_lines(diff)
+            .iter()
+            .map(|l| (l.prefix, l.content))
+            .collect();
+        assert_eq!(body, [(" ", "a"), ("-", "b")]);
+    }
+
+    #[test]
+    fn header_ranges_ignore_function_context() {
+        let header = "@@ -10 +12,4 @@ fn f(a: i32) -> i32 { a +1 -2 }";
+        assert_eq!(new_range(header), Some((12, 4)));
+        assert_eq!(old_range(header), Some((10, 1)));
+        assert_eq!(
+            old_ranges("@@@ -1,2 -3,4 +5,6 @@@").collect::<Vec<_>>(),
+            [(1, 2), (3, 4)]
+        );
+    }
+
+    #[test]
+    fn hunk_at_finds_the_hunk_containing_the_line() {
+        let diff = "diff --git a/a.rs b/a.rs\n--- a/a.rs\n+++ b/a.rs\n@@ -1 +1 @@\n-a\n+b\n\
+                    @@ -20,2 +20,2 @@\n x\n-y\n+z\n";
+        assert_eq!(
+            hunk_at(diff, "a.rs", 21).unwrap(),
+            ["@@ -20,2 +20,2 @@", " x", "-y", "+z"]
+        );
+        assert!(hunk_at(diff, "a.rs", 5).is_none());
+        assert!(hunk_at(diff, "b.rs", 1).is_none());
+    }
+}
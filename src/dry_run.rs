@@ -0,0 +1,81 @@
+use crate::config::Config;
+use crate::i18n::Msg;
+use crate::prompts::ReviewPrompt;
+use crate::{ai, tokens};
+use anyhow::{Context, Result};
+use colored::*;
+use serde_json::json;
+use std::fs;
+use std::path::Path;
+
+/// `check --dry-run`：输出每个 diff 块将要发送的完整请求，不发起任何网络调用。
+///
+/// API Key 不会出现在输出中。
+pub fn emit(
+    config: &Config,
+    prompt: &ReviewPrompt,
+    chunks: &[String],
+    budget: usize,
+    output: Option<&Path>,
+) -> Result<()> {
+    let system_tokens = tokens::count_message(&config.model, &prompt.system);
+
+    let requests = chunks
+        .iter()
+        .enumerate()
+        .map(|(i, chunk)| {
+            let request = ai::preview_check_request(config, prompt, chunk)?;
+            let diff_tokens = tokens::count_message(&config.model, &prompt.user_message(chunk));
+            Ok(json!({
+                "chunk": i + 1,
+                "estimated_prompt_tokens": system_tokens + diff_tokens,
+                "body": request,
+            }))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let preview = json!({
+        "url": ai::completions_url(config),
+        "model": config.model,
+        "fallback_models": config
+            .fallbacks
+            .iter()
+            .map(|f| json!({ "model": f.model, "base_url": f.base_url }))
+            .collect::<Vec<_>>(),
+        "triage_model": config.triage_model.as_ref().map(|t| t.model.clone()),
+        "deep_model": config.deep_model.as_ref().map(|d| d.model.clone()),
+        "consensus_models": config
+            .consensus_models
+            .iter()
+            .map(|c| json!({ "model": c.model, "base_url": c.base_url }))
+            .collect::<Vec<_>>(),
+        "context_window": tokens::context_window(&config.model, config.context_window),
+        "chunk_budget_tokens": budget,
+        "reserved_output_tokens": tokens::reserved_output(config),
+        "params": ai::model_params(config),
+        "system_prompt_tokens": system_tokens,
+        "requests": requests,
+    });
+    let rendered = serde_json::to_string_pretty(&preview)?;
+
+    match output {
+        Some(path) => {
+            fs::write(path, rendered)
+                .with_context(|| Msg::WriteFileFailed.fill(&[("path", format!("{:?}", path))]))?;
+            println!(
+                "{} {}",
+                Msg::DryRunLabel.text().cyan().bold(),
+                Msg::DryRunWritten.fill(&[
+                    ("count", chunks.len().to_string()),
+                    ("path", format!("{:?}", path)),
+                ])
+            );
+        }
+        None => {
+            println!("{}", rendered);
+            println!("\n{} {}", Msg::DryRunLabel.text().cyan().bold(), Msg::DryRunNoApi);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use crate::config::Config;
+use std::path::Path;
+
+/// 文件是否需要审查：扩展名在 `AI_CHECK_EXTENSIONS` 中，且不匹配 `AI_CHECK_EXCLUDE`。
+pub fn is_monitored(config: &Config, file: &Path) -> bool {
+    let name = file.to_string_lossy().replace('\\', "/");
+    let name = name.trim_start_matches("./");
+
+    config
+        .enabled_extensions
+        .iter()
+        .any(|ext| name.ends_with(ext.as_str()))
+        && !config
+            .exclude_patterns
+            .iter()
+            .any(|pattern| is_excluded(pattern, name))
+}
+
+/// 不含 `/` 的模式匹配任意一级目录名或文件名（如 `vendor`、`*.min.js`）；
+/// 含 `/` 的模式匹配完整的相对路径，以 `/` 结尾时匹配该目录下的所有文件。
+fn is_excluded(pattern: &str, path: &str) -> bool {
+    let pattern = pattern.trim_start_matches("./");
+    if let Some(dir) = pattern.strip_suffix('/') {
+        return glob_match(&format!("{}/**", dir), path);
+    }
+    if pattern.contains('/') {
+        return glob_match(pattern, path);
+    }
+    path.split('/').any(|component| glob_match(pattern, component))
+}
+
+/// 简单的通配符匹配：`**` 匹配任意字符，`*` 匹配除 `/` 以外的任意字符，`?` 匹配单个字符。
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let t: Vec<char> = text.chars().collect();
+    matches_from(&p, &t)
+}
+
+fn matches_from(p: &[char], t: &[char]) -> bool {
+    match p.first() {
+        None => t.is_empty(),
+        Some('*') if p.get(1) == Some(&'*') => match p[2..].strip_prefix(&['/']) {
+            // `**/` 匹配零级或多级目录，之后的部分必须从某一级的开头匹配
+            Some(rest) => (0..=t.len())
+                .filter(|&i| i == 0 || t[i - 1] == '/')
+                .any(|i| matches_from(rest, &t[i..])),
+            None => (0..=t.len()).any(|i| matches_from(&p[2..], &t[i..])),
+        },
+        Some('*') => (0..=t.len())
+            .take_while(|&i| i == 0 || t[i - 1] != '/')
+            .any(|i| matches_from(&p[1..], &t[i..])),
+        Some('?') => !t.is_empty() && t[0] != '/' && matches_from(&p[1..], &t[1..]),
+        Some(c) => t.first() == Some(c) && matches_from(&p[1..], &t[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_star_stays_within_one_component() {
+        assert!(glob_match("*.min.js", "app.min.js"));
+        assert!(glob_match("src/*.rs", "src/main.rs"));
+        assert!(!glob_match("src/*.rs", "src/git/mod.rs"));
+        assert!(!glob_match("*.rs", "main.rs.orig"));
+    }
+
+    #[test]
+    fn double_star_crosses_directories() {
+        assert!(glob_match("src/**/a.rs", "src/a.rs"));
+        assert!(glob_match("src/**/a.rs", "src/x/y/a.rs"));
+        assert!(!glob_match("src/**/a.rs", "src/xa.rs"));
+        assert!(glob_match("**/gen/*.ts", "web/gen/api.ts"));
+        assert!(glob_match("dist/**", "dist/js/app.js"));
+        assert!(!glob_match("dist/**", "distx/app.js"));
+        assert!(glob_match("src/**.rs", "src/git/mod.rs"));
+    }
+
+    #[test]
+    fn question_mark_matches_one_character_except_slash() {
+        assert!(glob_match("v?.rs", "v1.rs"));
+        assert!(!glob_match("v?.rs", "v10.rs"));
+        assert!(!glob_match("a?b", "a/b"));
+    }
+
+    #[test]
+    fn exclude_patterns_by_component_or_path() {
+        assert!(is_excluded("vendor", "lib/vendor/x.js"));
+        assert!(!is_excluded("vendor", "lib/vendors/x.js"));
+        assert!(is_excluded("*.min.js", "static/app.min.js"));
+        assert!(is_excluded("dist/", "dist/app.js"));
+        assert!(!is_excluded("dist/", "web/dist/app.js"));
+        assert!(is_excluded("./web/*.js", "web/app.js"));
+    }
+
+    #[test]
+    fn monitored_files_need_extension_and_no_exclude() {
+        let mut config = Config::load().unwrap();
+        config.enabled_extensions = vec![".rs".to_string(), ".ts".to_string()];
+        config.exclude_patterns = vec!["gen/".to_string(), "*.d.ts".to_string()];
+        assert!(is_monitored(&config, Path::new("./src/main.rs")));
+        assert!(is_monitored(&config, Path::new("src\\lib.rs")));
+        assert!(!is_monitored(&config, Path::new("README.md")));
+        assert!(!is_monitored(&config, Path::new("gen/api.rs")));
+        assert!(!is_monitored(&config, Path::new("types/index.d.ts")));
+    }
+}
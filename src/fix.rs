@@ -0,0 +1,264 @@
+use crate::ai::{AiCheckResult, Issue};
+use crate::config::Config;
+use crate::i18n::Msg;
+use crate::{diff, git, parse};
+use anyhow::{Context, Result};
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+/// 上一次检查中可自动修复的问题，保存在 `.git/ai_check/fixes.json`，供 `fix` 子命令使用。
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Fix {
+    pub location: String,
+    pub description: String,
+    pub patch: String,
+}
+
+/// 校验模型给出的补丁能否应用到暂存区，不能应用的直接丢弃。
+pub fn validate_patches(result: &mut AiCheckResult) {
+    let mut rejected = 0;
+    for issue in &mut result.list {
+        let Some(patch) = issue.patch.take() else {
+            continue;
+        };
+        let patch = normalize(&patch);
+        if patch.trim().is_empty() {
+            continue;
+        }
+        // `git apply --unidiff-zero` 不校验上下文，补丁可能落在与问题无关的位置
+        if targets_location(&patch, &issue.location) && git::check_patch(&patch).is_ok() {
+            issue.patch = Some(patch);
+        } else {
+            rejected += 1;
+        }
+    }
+
+    if rejected > 0 {
+        eprintln!(
+            "{} {}",
+            Msg::Warning.text().yellow(),
+            Msg::PatchesRejected.fill(&[("count", rejected.to_string())])
+        );
+    }
+}
+
+/// 保存本次检查中带补丁的问题，覆盖上一次的记录。写入失败只打印警告。
+pub fn save(config: &Config, result: &AiCheckResult) {
+    let fixes: Vec<Fix> = result
+        .list
+        .iter()
+        .filter_map(|issue| {
+            Some(Fix {
+                location: issue.location.clone(),
+                description: issue.description.clone(),
+                patch: issue.patch.clone()?,
+            })
+        })
+        .collect();
+
+    if let Err(e) = write(config, &fixes) {
+        eprintln!("{} {} {}", Msg::Warning.text().yellow(), Msg::PatchesSaveFailed, e);
+    }
+}
+
+/// `fix` 子命令：逐个展示上一次检查给出的补丁，确认后应用到工作区并重新暂存。
+pub fn run(config: &Config) -> Result<()> {
+    let fixes = read(config)?;
+    if fixes.is_empty() {
+        println!("{}", Msg::NoFixes.text().yellow());
+        return Ok(());
+    }
+
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let mut remaining = Vec::new();
+    let mut applied = 0;
+
+    for (i, fix) in fixes.into_iter().enumerate() {
+        println!(
+            "\n[{}] {} {}",
+            i + 1,
+            fix.location.white().italic(),
+            fix.description
+        );
+        print_patch(&fix.patch);
+
+        if let Err(e) = git::check_patch(&fix.patch) {
+            // 暂存区在检查之后发生了变化
+            println!("  {} {}", Msg::PatchStale.text().yellow(), e);
+            continue;
+        }
+
+        if confirm(&mut input, Msg::ApplyPatchPrompt.text())? {
+            match git::apply_patch(&fix.patch) {
+                Ok(()) => {
+                    applied += 1;
+                    println!("  {}", Msg::PatchApplied.text().green());
+                }
+                Err(e) => {
+                    println!("  {} {}", Msg::PatchApplyFailed.text().red(), e);
+                    remaining.push(fix);
+                }
+            }
+        } else {
+            remaining.push(fix);
+        }
+    }
+
+    write(config, &remaining)?;
+    println!(
+        "\n{} {}",
+        Msg::Done.text().cyan().bold(),
+        Msg::PatchesApplied.fill(&[("count", applied.to_string())])
+    );
+    Ok(())
+}
+
+/// 应用单个问题的补丁前展示内容并请求确认（交互模式使用）。
+pub fn apply_interactively(input: &mut impl BufRead, issue: &Issue) -> Result<bool> {
+    let Some(patch) = &issue.patch else {
+        return Ok(false);
+    };
+
+    print_patch(patch);
+    if !confirm(input, Msg::ApplyPatchPrompt.text())? {
+        return Ok(false);
+    }
+
+    git::apply_patch(patch)?;
+    println!("  {}", Msg::PatchAppliedStaged.text().green());
+    Ok(true)
+}
+
+fn confirm(input: &mut impl BufRead, question: &str) -> Result<bool> {
+    print!("  {} {} [y/N]: ", "?".yellow().bold(), question);
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    input.read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+fn print_patch(patch: &str) {
+    for line in patch.lines() {
+        let colored = match line.chars().next() {
+            _ if line.starts_with("+++") || line.starts_with("---") => line.bold(),
+            Some('+') => line.green(),
+            Some('-') => line.red(),
+            Some('@') => line.cyan(),
+            _ => line.normal(),
+        };
+        println!("    {}", colored);
+    }
+}
+
+/// 补丁修改的行与问题位置相差不超过该行数时视为针对该问题。
+const LOCATION_TOLERANCE: usize = 3;
+
+/// 补丁是否只修改问题所在的文件，且每个 hunk 修改的行都在问题位置附近。
+///
+/// 问题位置指暂存区中的行号，也就是补丁的原文件一侧。位置无法解析时无从核对，视为不符。
+fn targets_location(patch: &str, location: &str) -> bool {
+    let Some((file, line)) = diff::parse_location(location) else {
+        return false;
+    };
+
+    if diff::header_paths(patch)
+        .iter()
+        .any(|path| *path != "/dev/null" && *path != file)
+    {
+        return false;
+    }
+
+    let hunks = diff::hunk_headers(patch);
+    !hunks.is_empty()
+        && hunks.iter().all(|header| {
+            let Some((start, count)) = diff::old_range(header) else {
+                return false;
+            };
+            // 纯新增的 hunk 行数为 0，插入位置在 start 之后
+            let end = start + count.max(1) - 1;
+            line + LOCATION_TOLERANCE >= start && line <= end + LOCATION_TOLERANCE
+        })
+}
+
+/// 去掉代码块标记，并保证以换行结尾（`git apply` 要求）。
+fn normalize(patch: &str) -> String {
+    let mut patch = parse::strip_code_fence(patch).to_string();
+    if !patch.ends_with('\n') {
+        patch.push('\n');
+    }
+    patch
+}
+
+fn fixes_path(config: &Config) -> Result<PathBuf> {
+    Ok(git::backend(config)
+        .git_dir()?
+        .join("ai_check")
+        .join("fixes.json"))
+}
+
+fn read(config: &Config) -> Result<Vec<Fix>> {
+    let path = fixes_path(config)?;
+    match fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content)
+            .with_context(|| Msg::ParseFileFailed.fill(&[("path", format!("{:?}", path))])),
+        Err(_) => Ok(Vec::new()),
+    }
+}
+
+fn write(config: &Config, fixes: &[Fix]) -> Result<()> {
+    let path = fixes_path(config)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(fixes)?)
+        .with_context(|| Msg::WriteFileFailed.fill(&[("path", format!("{:?}", path))]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATCH: &str = "--- a/src/a.rs\n+++ b/src/a.rs\n@@ -10,2 +10,2 @@\n-let x = 1;\n+let x = 2;\n keep\n";
+
+    #[test]
+    fn patch_near_the_issue_is_accepted() {
+        assert!(targets_location(PATCH, "src/a.rs:10"));
+        assert!(targets_location(PATCH, "src/a.rs:14"));
+        assert!(targets_location(PATCH, "src/a.rs:7"));
+    }
+
+    #[test]
+    fn patch_far_from_the_issue_or_in_another_file_is_rejected() {
+        assert!(!targets_location(PATCH, "src/a.rs:15"));
+        assert!(!targets_location(PATCH, "src/a.rs:6"));
+        assert!(!targets_location(PATCH, "src/b.rs:10"));
+        assert!(!targets_location(PATCH, "整个文件"));
+    }
+
+    #[test]
+    fn every_hunk_must_be_near_the_issue() {
+        let patch = format!("{}@@ -80 +80 @@\n-a\n+b\n", PATCH);
+        assert!(!targets_location(&patch, "src/a.rs:10"));
+        assert!(!targets_location("--- a/src/a.rs\n+++ b/src/a.rs\n", "src/a.rs:10"));
+    }
+
+    #[test]
+    fn insertions_and_new_files_are_located_by_start_line() {
+        let insert = "--- a/src/a.rs\n+++ b/src/a.rs\n@@ -20,0 +21 @@\n+check();\n";
+        assert!(targets_location(insert, "src/a.rs:21"));
+        let new_file = "--- /dev/null\n+++ b/src/a.rs\n@@ -0,0 +1 @@\n+fn main() {}\n";
+        assert!(targets_location(new_file, "src/a.rs:1"));
+    }
+
+    #[test]
+    fn removed_lines_that_look_like_headers_are_content() {
+        // 删除 `-- comment` 行时补丁中出现 `--- comment`，不是文件头
+        let patch = "--- a/db.sql\n+++ b/db.sql\n@@ -3,2 +3 @@\n--- comment\n SELECT 1;\n";
+        assert!(targets_location(patch, "db.sql:3"));
+    }
+}
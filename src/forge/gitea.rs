@@ -0,0 +1,115 @@
+use super::{comments_from, non_empty_var, pr_from_ref, token, Api, Comment, Forge, InlineComment};
+use crate::config::Config;
+use crate::i18n::Msg;
+use anyhow::{Context, Result};
+use reqwest::Method;
+use serde_json::json;
+use tokio::sync::OnceCell;
+
+/// Gitea（含 Forgejo）的 PR 评论接口。行内评论只能通过 review 发表。
+pub struct Gitea {
+    api: Api,
+    repo: String,
+    pr: u64,
+    /// PR 的 head 提交，发表第一条行内评论时读取
+    head: OnceCell<String>,
+}
+
+impl Gitea {
+    /// Gitea Actions 提供与 GitHub Actions 兼容的环境变量；自建实例没有 API 地址时需要配置。
+    pub fn from_env(config: &Config, pr: Option<u64>) -> Result<Gitea> {
+        let base = config
+            .forge_api
+            .clone()
+            .or_else(|| non_empty_var("GITHUB_API_URL"))
+            .or_else(|| non_empty_var("GITHUB_SERVER_URL").map(|url| format!("{}/api/v1", url)))
+            .context(Msg::GiteaApiUnknown.text())?;
+        let token = token(config, &["GITEA_TOKEN", "GITHUB_TOKEN"])?;
+        let repo = non_empty_var("GITHUB_REPOSITORY")
+            .context(Msg::EnvVarMissing.fill(&[("name", "GITHUB_REPOSITORY".to_string())]))?;
+        let pr = pr
+            .or_else(|| non_empty_var("GITHUB_REF").and_then(|r| pr_from_ref(&r)))
+            .context(Msg::ForgePrUnknown.text())?;
+
+        Ok(Gitea {
+            api: Api::new(config, base, ("Authorization", format!("token {}", token)))?,
+            repo,
+            pr,
+            head: OnceCell::new(),
+        })
+    }
+}
+
+impl Forge for Gitea {
+    fn name(&self) -> &'static str {
+        "Gitea"
+    }
+
+    async fn comments(&self) -> Result<Vec<Comment>> {
+        let path = format!("/repos/{}/issues/{}/comments", self.repo, self.pr);
+        Ok(comments_from(self.api.get_all(&path, "limit").await?))
+    }
+
+    async fn inline_comments(&self) -> Result<Vec<Comment>> {
+        let path = format!("/repos/{}/pulls/{}/reviews", self.repo, self.pr);
+        let mut comments = Vec::new();
+        for review in self.api.get_all(&path, "limit").await? {
+            let Some(id) = review["id"].as_u64() else {
+                continue;
+            };
+            let list = self.api.get(&format!("{}/{}/comments", path, id)).await?;
+            if let serde_json::Value::Array(list) = list {
+                comments.extend(comments_from(list));
+            }
+        }
+        Ok(comments)
+    }
+
+    async fn create_comment(&self, body: &str) -> Result<()> {
+        let path = format!("/repos/{}/issues/{}/comments", self.repo, self.pr);
+        self.api.post(&path, json!({ "body": body })).await?;
+        Ok(())
+    }
+
+    async fn update_comment(&self, id: u64, body: &str) -> Result<()> {
+        let path = format!("/repos/{}/issues/comments/{}", self.repo, id);
+        self.api
+            .send(Method::PATCH, &path, Some(&json!({ "body": body })))
+            .await?;
+        Ok(())
+    }
+
+    async fn create_inline(&self, comment: &InlineComment) -> Result<()> {
+        let sha = self
+            .head
+            .get_or_try_init(|| async {
+                let pull = self
+                    .api
+                    .get(&format!("/repos/{}/pulls/{}", self.repo, self.pr))
+                    .await?;
+                pull["head"]["sha"]
+                    .as_str()
+                    .map(str::to_string)
+                    .context(Msg::ForgeHeadMissing.text())
+            })
+            .await?;
+
+        // 每条评论单独作为一个 review 发表，一条位置失效不会连累其他评论
+        self.api
+            .post(
+                &format!("/repos/{}/pulls/{}/reviews", self.repo, self.pr),
+                json!({
+                    "event": "COMMENT",
+                    "body": Msg::SummaryTitle.text(),
+                    "commit_id": sha,
+                    "comments": [{
+                        "path": comment.path,
+                        "body": comment.body,
+                        "new_position": comment.line,
+                    }],
+                }),
+            )
+            .await?;
+        Ok(())
+    }
+}
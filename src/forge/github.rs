@@ -0,0 +1,106 @@
+use super::{comments_from, non_empty_var, pr_from_ref, token, Api, Comment, Forge, InlineComment};
+use crate::config::Config;
+use crate::i18n::Msg;
+use anyhow::{Context, Result};
+use reqwest::Method;
+use serde_json::json;
+use tokio::sync::OnceCell;
+
+/// GitHub（含 GitHub Enterprise）的 PR 评论接口。
+pub struct GitHub {
+    api: Api,
+    repo: String,
+    pr: u64,
+    /// PR 的 head 提交，发表第一条行内评论时读取
+    head: OnceCell<String>,
+}
+
+impl GitHub {
+    /// 从 GitHub Actions 的环境变量读取仓库和 PR 编号。
+    pub fn from_env(config: &Config, pr: Option<u64>) -> Result<GitHub> {
+        let base = config
+            .forge_api
+            .clone()
+            .or_else(|| non_empty_var("GITHUB_API_URL"))
+            .unwrap_or_else(|| "https://api.github.com".to_string());
+        let token = token(config, &["GITHUB_TOKEN"])?;
+        let repo = non_empty_var("GITHUB_REPOSITORY")
+            .context(Msg::EnvVarMissing.fill(&[("name", "GITHUB_REPOSITORY".to_string())]))?;
+        let pr = pr
+            .or_else(|| non_empty_var("GITHUB_REF").and_then(|r| pr_from_ref(&r)))
+            .context(Msg::ForgePrUnknown.text())?;
+
+        Ok(GitHub {
+            api: Api::new(config, base, ("Authorization", format!("Bearer {}", token)))?,
+            repo,
+            pr,
+            head: OnceCell::new(),
+        })
+    }
+
+    /// 行内评论需要指定 PR 当前的 head 提交。
+    async fn head_sha(&self) -> Result<&str> {
+        let sha = self
+            .head
+            .get_or_try_init(|| async {
+                let pull = self
+                    .api
+                    .get(&format!("/repos/{}/pulls/{}", self.repo, self.pr))
+                    .await?;
+                pull["head"]["sha"]
+                    .as_str()
+                    .map(str::to_string)
+                    .context(Msg::ForgeHeadMissing.text())
+            })
+            .await?;
+        Ok(sha)
+    }
+}
+
+impl Forge for GitHub {
+    fn name(&self) -> &'static str {
+        "GitHub"
+    }
+
+    async fn comments(&self) -> Result<Vec<Comment>> {
+        let path = format!("/repos/{}/issues/{}/comments", self.repo, self.pr);
+        Ok(comments_from(self.api.get_all(&path, "per_page").await?))
+    }
+
+    async fn inline_comments(&self) -> Result<Vec<Comment>> {
+        let path = format!("/repos/{}/pulls/{}/comments", self.repo, self.pr);
+        Ok(comments_from(self.api.get_all(&path, "per_page").await?))
+    }
+
+    async fn create_comment(&self, body: &str) -> Result<()> {
+        let path = format!("/repos/{}/issues/{}/comments", self.repo, self.pr);
+        self.api.post(&path, json!({ "body": body })).await?;
+        Ok(())
+    }
+
+    async fn update_comment(&self, id: u64, body: &str) -> Result<()> {
+        let path = format!("/repos/{}/issues/comments/{}", self.repo, id);
+        self.api
+            .send(Method::PATCH, &path, Some(&json!({ "body": body })))
+            .await?;
+        Ok(())
+    }
+
+    async fn create_inline(&self, comment: &InlineComment) -> Result<()> {
+        let sha = self.head_sha().await?;
+        let path = format!("/repos/{}/pulls/{}/comments", self.repo, self.pr);
+        self.api
+            .post(
+                &path,
+                json!({
+                    "body": comment.body,
+                    "commit_id": sha,
+                    "path": comment.path,
+                    "line": comment.line,
+                    "side": "RIGHT",
+                }),
+            )
+            .await?;
+        Ok(())
+    }
+}
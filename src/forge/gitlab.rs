@@ -0,0 +1,127 @@
+use super::{comments_from, non_empty_var, token, Api, Comment, Forge, InlineComment};
+use crate::config::Config;
+use crate::i18n::Msg;
+use anyhow::{Context, Result};
+use reqwest::Method;
+use serde_json::{json, Value};
+use tokio::sync::OnceCell;
+
+/// GitLab 的 MR 评论（note）接口。
+pub struct GitLab {
+    api: Api,
+    project: String,
+    mr: u64,
+    /// MR 当前的三个版本 sha，发表第一条行内评论时读取
+    diff_refs: OnceCell<Value>,
+}
+
+impl GitLab {
+    /// 从 GitLab CI 的环境变量读取项目和 MR 编号。
+    pub fn from_env(config: &Config, mr: Option<u64>) -> Result<GitLab> {
+        let base = config
+            .forge_api
+            .clone()
+            .or_else(|| non_empty_var("CI_API_V4_URL"))
+            .unwrap_or_else(|| "https://gitlab.com/api/v4".to_string());
+        let token = token(config, &["GITLAB_TOKEN"])?;
+        // 项目路径作为 id 使用时需要把 `/` 编码
+        let project = non_empty_var("CI_PROJECT_ID")
+            .or_else(|| non_empty_var("CI_PROJECT_PATH").map(|p| p.replace('/', "%2F")))
+            .context(Msg::EnvVarMissing.fill(&[("name", "CI_PROJECT_ID".to_string())]))?;
+        let mr = mr
+            .or_else(|| non_empty_var("CI_MERGE_REQUEST_IID").and_then(|v| v.parse().ok()))
+            .context(Msg::ForgeMrUnknown.text())?;
+
+        Ok(GitLab {
+            api: Api::new(config, base, ("PRIVATE-TOKEN", token))?,
+            project,
+            mr,
+            diff_refs: OnceCell::new(),
+        })
+    }
+
+    fn notes_path(&self) -> String {
+        format!(
+            "/projects/{}/merge_requests/{}/notes",
+            self.project, self.mr
+        )
+    }
+
+    /// 读取全部 note，按是否为 diff 上的评论（`DiffNote`）筛选。
+    async fn notes(&self, diff_notes: bool) -> Result<Vec<Comment>> {
+        let notes = self.api.get_all(&self.notes_path(), "per_page").await?;
+        Ok(comments_from(
+            notes
+                .into_iter()
+                .filter(|n| (n["type"].as_str() == Some("DiffNote")) == diff_notes)
+                .collect(),
+        ))
+    }
+}
+
+impl Forge for GitLab {
+    fn name(&self) -> &'static str {
+        "GitLab"
+    }
+
+    async fn comments(&self) -> Result<Vec<Comment>> {
+        self.notes(false).await
+    }
+
+    async fn inline_comments(&self) -> Result<Vec<Comment>> {
+        self.notes(true).await
+    }
+
+    async fn create_comment(&self, body: &str) -> Result<()> {
+        self.api
+            .post(&self.notes_path(), json!({ "body": body }))
+            .await?;
+        Ok(())
+    }
+
+    async fn update_comment(&self, id: u64, body: &str) -> Result<()> {
+        let path = format!("{}/{}", self.notes_path(), id);
+        self.api
+            .send(Method::PUT, &path, Some(&json!({ "body": body })))
+            .await?;
+        Ok(())
+    }
+
+    async fn create_inline(&self, comment: &InlineComment) -> Result<()> {
+        // diff 上的讨论需要 MR 当前的三个版本 sha
+        let refs = self
+            .diff_refs
+            .get_or_try_init(|| async {
+                let mr = self
+                    .api
+                    .get(&format!(
+                        "/projects/{}/merge_requests/{}",
+                        self.project, self.mr
+                    ))
+                    .await?;
+                let refs = mr["diff_refs"].clone();
+                if refs.is_null() {
+                    anyhow::bail!(Msg::ForgeDiffRefsMissing.text());
+                }
+                Ok(refs)
+            })
+            .await?;
+
+        let path = format!(
+            "/projects/{}/merge_requests/{}/discussions",
+            self.project, self.mr
+        );
+        let position: Value = json!({
+            "position_type": "text",
+            "base_sha": refs["base_sha"],
+            "start_sha": refs["start_sha"],
+            "head_sha": refs["head_sha"],
+            "new_path": comment.path,
+            "new_line": comment.line,
+        });
+        self.api
+            .post(&path, json!({ "body": comment.body, "position": position }))
+            .await?;
+        Ok(())
+    }
+}
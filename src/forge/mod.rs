@@ -0,0 +1,727 @@
+mod gitea;
+mod github;
+mod gitlab;
+
+use crate::ai::{AiCheckResult, Issue};
+use crate::config::Config;
+use crate::i18n::Msg;
+use crate::{ci, diff, digest};
+use anyhow::{bail, Context, Result};
+use reqwest::{Client, Method};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::env;
+
+pub use gitea::Gitea;
+pub use github::GitHub;
+pub use gitlab::GitLab;
+
+/// 汇总评论中的隐藏标记，重新运行时据此找到并更新同一条评论。
+const SUMMARY_MARKER: &str = "<!-- ai-check:summary -->";
+
+/// 分页读取评论时的每页数量和最大页数
+const PAGE_SIZE: usize = 100;
+const MAX_PAGES: usize = 20;
+
+/// PR 中已有的一条评论。
+pub struct Comment {
+    pub id: u64,
+    pub body: String,
+}
+
+/// 发表在 PR diff 某一新增行上的评论。
+pub struct InlineComment {
+    pub path: String,
+    pub line: usize,
+    pub body: String,
+}
+
+/// 代码托管平台的 PR/MR 评论接口。
+///
+/// 各平台只实现最基本的读写操作；位置校验、去重和评论内容由 [`publish`] 统一处理。
+pub trait Forge {
+    fn name(&self) -> &'static str;
+
+    /// PR 下的普通评论（不含行内评论）。
+    async fn comments(&self) -> Result<Vec<Comment>>;
+
+    /// PR 中已有的行内评论。
+    async fn inline_comments(&self) -> Result<Vec<Comment>>;
+
+    async fn create_comment(&self, body: &str) -> Result<()>;
+
+    async fn update_comment(&self, id: u64, body: &str) -> Result<()>;
+
+    /// 发表一条行内评论，位置已确认是 PR diff 中的新增行。
+    async fn create_inline(&self, comment: &InlineComment) -> Result<()>;
+}
+
+/// 一次发表的统计。
+pub struct Published {
+    pub forge: &'static str,
+    /// 新发表的行内评论数
+    pub inline: usize,
+    /// 已存在而跳过的行内评论数
+    pub duplicates: usize,
+    /// 汇总评论是更新已有的还是新建的
+    pub updated: bool,
+    /// 发表失败的行内评论，不影响其他评论
+    pub failures: Vec<anyhow::Error>,
+}
+
+/// 把审查结果发表到当前 PR：一条汇总评论（重复运行时原地更新），
+/// 以及指向 diff 新增行的行内评论（已发表过的不再重复发表）。
+pub async fn publish(
+    config: &Config,
+    platform: ci::Platform,
+    result: &AiCheckResult,
+    diff_text: &str,
+    failed: bool,
+    pr: Option<u64>,
+) -> Result<Published> {
+    let kind = match config.forge.as_str() {
+        "github" => "github",
+        "gitlab" => "gitlab",
+        "gitea" => "gitea",
+        // Gitea Actions 兼容 GitHub Actions 的环境变量，需要先判断
+        _ if env::var("GITEA_ACTIONS").is_ok_and(|v| v == "true") => "gitea",
+        _ => match platform {
+            ci::Platform::GitHub => "github",
+            ci::Platform::GitLab => "gitlab",
+            ci::Platform::Other => bail!(Msg::ForgeUnknown.text()),
+        },
+    };
+
+    match kind {
+        "github" => post(&GitHub::from_env(config, pr)?, config, result, diff_text, failed).await,
+        "gitlab" => post(&GitLab::from_env(config, pr)?, config, result, diff_text, failed).await,
+        _ => post(&Gitea::from_env(config, pr)?, config, result, diff_text, failed).await,
+    }
+}
+
+/// 先发表或更新汇总评论，再逐条发表行内评论。汇总评论失败时返回错误；
+/// 单条行内评论失败（例如该行已不在最新的 diff 中）只记录下来，不影响其他评论。
+async fn post(
+    forge: &impl Forge,
+    config: &Config,
+    result: &AiCheckResult,
+    diff_text: &str,
+    failed: bool,
+) -> Result<Published> {
+    let body = format!(
+        "{}\n{}",
+        SUMMARY_MARKER,
+        ci::summary_markdown(config, result, failed)
+    );
+    let summary = forge
+        .comments()
+        .await
+        .context(Msg::ForgeCommentsFailed.text())?
+        .into_iter()
+        .find(|c| c.body.contains(SUMMARY_MARKER));
+    let updated = summary.is_some();
+    match summary {
+        Some(comment) => forge.update_comment(comment.id, &body).await,
+        None => forge.create_comment(&body).await,
+    }
+    .context(Msg::ForgeSummaryFailed.text())?;
+
+    let mut published = Published {
+        forge: forge.name(),
+        inline: 0,
+        duplicates: 0,
+        updated,
+        failures: Vec::new(),
+    };
+
+    let existing: Vec<String> = match forge.inline_comments().await {
+        Ok(comments) => comments.into_iter().map(|c| c.body).collect(),
+        Err(e) => {
+            // 无法去重时不发表行内评论，避免重复
+            published
+                .failures
+                .push(e.context(Msg::ForgeInlineListFailed.text()));
+            return Ok(published);
+        }
+    };
+
+    for comment in inline_comments(result, diff_text, &existing, &mut published.duplicates) {
+        match forge.create_inline(&comment).await {
+            Ok(()) => published.inline += 1,
+            Err(e) => published.failures.push(e.context(Msg::ForgeInlineFailed.fill(&[
+                ("path", comment.path.clone()),
+                ("line", comment.line.to_string()),
+            ]))),
+        }
+    }
+
+    Ok(published)
+}
+
+/// 需要新发表的行内评论。平台只接受落在 PR diff 中的行内评论，其余问题只出现在汇总评论里；
+/// 隐藏标记已出现在 `existing` 中的问题计入 `duplicates` 后跳过。
+fn inline_comments(
+    result: &AiCheckResult,
+    diff_text: &str,
+    existing: &[String],
+    duplicates: &mut usize,
+) -> Vec<InlineComment> {
+    let added: HashMap<(String, usize), &str> = diff::added_lines(diff_text)
+        .into_iter()
+        .map(|l| ((l.file, l.line_no), l.content.trim()))
+        .collect();
+
+    let mut inline: Vec<InlineComment> = Vec::new();
+    for issue in &result.list {
+        let Some((file, line)) = diff::parse_location(&issue.location) else {
+            continue;
+        };
+        let Some(code) = added.get(&(file.to_string(), line)) else {
+            continue;
+        };
+
+        let marker = inline_marker(issue, file, code);
+        if existing.iter().any(|body| body.contains(&marker)) {
+            *duplicates += 1;
+            continue;
+        }
+        if inline.iter().any(|c| c.body.contains(&marker)) {
+            continue;
+        }
+        inline.push(InlineComment {
+            path: file.to_string(),
+            line,
+            body: inline_body(issue, &marker),
+        });
+    }
+    inline
+}
+
+/// 行内评论的隐藏标记：按文件、视角、被评论代码行的内容和问题描述生成，代码行移动后
+/// 仍能识别；同一行上不同的问题各自发表。使用稳定的摘要，升级工具后也不会重复评论。
+fn inline_marker(issue: &Issue, file: &str, code: &str) -> String {
+    let fingerprint = digest::fingerprint(&[
+        file,
+        &digest::normalize(&issue.perspective),
+        code.trim(),
+        &digest::normalize(&issue.description),
+    ]);
+    format!("<!-- ai-check:{} -->", fingerprint)
+}
+
+fn inline_body(issue: &Issue, marker: &str) -> String {
+    let mut body = format!(
+        "**[{}] {}**\n\n{}\n",
+        issue.severity.trim().to_lowercase(),
+        issue.perspective.trim(),
+        issue.description.trim()
+    );
+    if !issue.suggestion.trim().is_empty() {
+        body.push_str(&format!("\n{} {}\n", Msg::SuggestionLabel, issue.suggestion.trim()));
+    }
+    body.push_str(&format!("\n{}", marker));
+    body
+}
+
+/// 平台访问令牌：`AI_CHECK_FORGE_TOKEN` 优先，其次是 CI 中常见的环境变量。
+fn token(config: &Config, fallbacks: &[&str]) -> Result<String> {
+    config
+        .forge_token
+        .clone()
+        .or_else(|| fallbacks.iter().find_map(|name| non_empty_var(name)))
+        .with_context(|| Msg::ForgeTokenMissing.fill(&[("vars", fallbacks.join(" / "))]))
+}
+
+/// 从 `refs/pull/<n>/merge` 形式的引用中取出 PR 编号。
+fn pr_from_ref(git_ref: &str) -> Option<u64> {
+    git_ref
+        .strip_prefix("refs/pull/")?
+        .split('/')
+        .next()?
+        .parse()
+        .ok()
+}
+
+fn non_empty_var(name: &str) -> Option<String> {
+    env::var(name).ok().filter(|v| !v.trim().is_empty())
+}
+
+/// 各平台共用的 JSON API 客户端，每个请求都带上认证头。
+struct Api {
+    client: Client,
+    base: String,
+    auth: (&'static str, String),
+}
+
+impl Api {
+    fn new(config: &Config, base: String, auth: (&'static str, String)) -> Result<Api> {
+        let mut client = Client::builder().user_agent("ai_git_pre_commit");
+        if config.timeout_secs > 0 {
+            client = client.timeout(std::time::Duration::from_secs(config.timeout_secs));
+        }
+        Ok(Api {
+            client: client.build().context(Msg::HttpClientFailed.text())?,
+            base: base.trim_end_matches('/').to_string(),
+            auth,
+        })
+    }
+
+    async fn send(&self, method: Method, path: &str, body: Option<&Value>) -> Result<Value> {
+        let url = format!("{}{}", self.base, path);
+        let mut request = self
+            .client
+            .request(method.clone(), &url)
+            .header(self.auth.0, &self.auth.1)
+            .header("Accept", "application/json");
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+
+        let res = request
+            .send()
+            .await
+            .with_context(|| Msg::ForgeRequestFailed.fill(&[("url", url.clone())]))?;
+        let status = res.status();
+        let text = res.text().await?;
+        if !status.is_success() {
+            bail!(Msg::ForgeStatus.fill(&[
+                ("request", format!("{} {}", method, path)),
+                ("status", status.to_string()),
+                ("body", text.trim().to_string()),
+            ]));
+        }
+        if text.trim().is_empty() {
+            return Ok(Value::Null);
+        }
+        serde_json::from_str(&text)
+            .with_context(|| Msg::ForgeResponseInvalid.fill(&[("url", url.clone())]))
+    }
+
+    async fn get(&self, path: &str) -> Result<Value> {
+        self.send(Method::GET, path, None).await
+    }
+
+    async fn post(&self, path: &str, body: Value) -> Result<Value> {
+        self.send(Method::POST, path, Some(&body)).await
+    }
+
+    /// 逐页读取列表接口；`size_param` 为平台的每页数量参数名（`per_page` 或 `limit`）。
+    async fn get_all(&self, path: &str, size_param: &str) -> Result<Vec<Value>> {
+        let mut items = Vec::new();
+        for page in 1..=MAX_PAGES {
+            let url = format!("{}?{}={}&page={}", path, size_param, PAGE_SIZE, page);
+            let batch = match self.get(&url).await? {
+                Value::Array(batch) => batch,
+                other => bail!(Msg::ForgeNotList.fill(&[
+                    ("path", path.to_string()),
+                    ("body", other.to_string()),
+                ])),
+            };
+            let done = batch.len() < PAGE_SIZE;
+            items.extend(batch);
+            if done {
+                break;
+            }
+        }
+        Ok(items)
+    }
+}
+
+/// 从评论列表接口的响应中取出 id 和正文。
+fn comments_from(values: Vec<Value>) -> Vec<Comment> {
+    values
+        .into_iter()
+        .filter_map(|v| {
+            Some(Comment {
+                id: v.get("id")?.as_u64()?,
+                body: v.get("body")?.as_str()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+
+    const DIFF: &str = "diff --git a/src/a.rs b/src/a.rs\n\
+--- a/src/a.rs\n\
++++ b/src/a.rs\n\
+@@ -1,0 +2,2 @@\n\
++let password = input;\n\
++let q = format!(\"select {}\", x);\n";
+
+    #[derive(Default, Clone, Copy, PartialEq)]
+    enum Kind {
+        #[default]
+        GitHub,
+        GitLab,
+        Gitea,
+    }
+
+    /// 只实现发表评论用到的几个接口的 GitHub / GitLab / Gitea API。
+    #[derive(Default)]
+    struct FakeForge {
+        kind: Kind,
+        comments: Vec<(u64, String)>,
+        /// 行内评论的路径、行号和内容
+        inline: Vec<(String, u64, String)>,
+        updates: usize,
+        next_id: u64,
+        /// 发表到该行的行内评论返回 422，模拟位置已失效
+        reject_line: Option<u64>,
+        /// 按时间顺序记录的写操作：`summary` 或 `inline`
+        events: Vec<&'static str>,
+    }
+
+    impl FakeForge {
+        fn new(kind: Kind) -> Arc<Mutex<FakeForge>> {
+            Arc::new(Mutex::new(FakeForge {
+                kind,
+                ..FakeForge::default()
+            }))
+        }
+
+        fn handle(&mut self, method: &str, path: &str, body: &Value) -> (u16, Value) {
+            let path = path.split('?').next().unwrap_or(path);
+            match self.kind {
+                Kind::GitHub => self.handle_github(method, path, body),
+                Kind::GitLab => self.handle_gitlab(method, path, body),
+                Kind::Gitea => self.handle_gitea(method, path, body),
+            }
+        }
+
+        fn comment_list(&self) -> Value {
+            self.comments
+                .iter()
+                .map(|(id, body)| json!({ "id": id, "body": body }))
+                .collect()
+        }
+
+        fn create_comment(&mut self, body: &Value) -> (u16, Value) {
+            self.next_id += 1;
+            let body = body["body"].as_str().unwrap_or_default().to_string();
+            self.comments.push((self.next_id, body));
+            self.events.push("summary");
+            (201, json!({ "id": self.next_id }))
+        }
+
+        fn update_comment(&mut self, path: &str, body: &Value) -> (u16, Value) {
+            let id: u64 = path.rsplit('/').next().unwrap().parse().unwrap();
+            let body = body["body"].as_str().unwrap_or_default().to_string();
+            match self.comments.iter_mut().find(|(c, _)| *c == id) {
+                Some(comment) => {
+                    comment.1 = body;
+                    self.updates += 1;
+                    self.events.push("summary");
+                    (200, json!({ "id": id }))
+                }
+                None => (404, json!({ "message": "Not Found" })),
+            }
+        }
+
+        fn create_inline(&mut self, path: &Value, line: &Value, body: &Value) -> (u16, Value) {
+            let line = line.as_u64().unwrap_or_default();
+            if self.reject_line == Some(line) {
+                return (422, json!({ "message": "line must be part of the diff" }));
+            }
+            let path = path.as_str().unwrap_or_default().to_string();
+            let text = body.as_str().unwrap_or_default().to_string();
+            self.inline.push((path, line, text));
+            self.events.push("inline");
+            (201, json!({ "id": self.inline.len() }))
+        }
+
+        fn handle_github(&mut self, method: &str, path: &str, body: &Value) -> (u16, Value) {
+            match (method, path) {
+                ("GET", "/repos/o/r/pulls/7") => (200, json!({ "head": { "sha": "abc123" } })),
+                ("GET", "/repos/o/r/issues/7/comments") => (200, self.comment_list()),
+                ("POST", "/repos/o/r/issues/7/comments") => self.create_comment(body),
+                ("PATCH", _) if path.starts_with("/repos/o/r/issues/comments/") => {
+                    self.update_comment(path, body)
+                }
+                ("GET", "/repos/o/r/pulls/7/comments") => (
+                    200,
+                    self.inline
+                        .iter()
+                        .enumerate()
+                        .map(|(id, (_, _, body))| json!({ "id": id, "body": body }))
+                        .collect(),
+                ),
+                ("POST", "/repos/o/r/pulls/7/comments") => {
+                    self.create_inline(&body["path"], &body["line"], &body["body"])
+                }
+                _ => (404, json!({ "message": "Not Found" })),
+            }
+        }
+
+        fn handle_gitlab(&mut self, method: &str, path: &str, body: &Value) -> (u16, Value) {
+            const MR: &str = "/projects/42/merge_requests/7";
+            let Some(rest) = path.strip_prefix(MR) else {
+                return (404, json!({ "message": "404 Not Found" }));
+            };
+            match (method, rest) {
+                ("GET", "") => (
+                    200,
+                    json!({ "diff_refs": { "base_sha": "b", "start_sha": "s", "head_sha": "h" } }),
+                ),
+                // 普通 note 和 diff 上的 note 在同一个列表中，按 type 区分
+                ("GET", "/notes") => {
+                    let mut notes: Vec<Value> = self
+                        .comments
+                        .iter()
+                        .map(|(id, body)| json!({ "id": id, "body": body, "type": null }))
+                        .collect();
+                    notes.extend(self.inline.iter().enumerate().map(|(id, (_, _, body))| {
+                        json!({ "id": 1000 + id, "body": body, "type": "DiffNote" })
+                    }));
+                    (200, Value::Array(notes))
+                }
+                ("POST", "/notes") => self.create_comment(body),
+                ("PUT", _) if rest.starts_with("/notes/") => self.update_comment(rest, body),
+                ("POST", "/discussions") => {
+                    let position = &body["position"];
+                    if position["head_sha"] != "h" {
+                        return (400, json!({ "message": "missing diff refs" }));
+                    }
+                    self.create_inline(&position["new_path"], &position["new_line"], &body["body"])
+                }
+                _ => (404, json!({ "message": "404 Not Found" })),
+            }
+        }
+
+        fn handle_gitea(&mut self, method: &str, path: &str, body: &Value) -> (u16, Value) {
+            const REVIEWS: &str = "/repos/o/r/pulls/7/reviews";
+            match (method, path) {
+                ("GET", "/repos/o/r/pulls/7") => (200, json!({ "head": { "sha": "abc123" } })),
+                ("GET", "/repos/o/r/issues/7/comments") => (200, self.comment_list()),
+                ("POST", "/repos/o/r/issues/7/comments") => self.create_comment(body),
+                ("PATCH", _) if path.starts_with("/repos/o/r/issues/comments/") => {
+                    self.update_comment(path, body)
+                }
+                // 每条行内评论是一个单独的 review
+                ("GET", REVIEWS) => (
+                    200,
+                    (1..=self.inline.len()).map(|id| json!({ "id": id })).collect(),
+                ),
+                ("GET", _) if path.starts_with(REVIEWS) && path.ends_with("/comments") => {
+                    let id: usize = path[REVIEWS.len() + 1..]
+                        .trim_end_matches("/comments")
+                        .parse()
+                        .unwrap();
+                    match self.inline.get(id - 1) {
+                        Some((_, _, body)) => (200, json!([{ "id": id, "body": body }])),
+                        None => (404, json!({ "message": "Not Found" })),
+                    }
+                }
+                ("POST", REVIEWS) => {
+                    if body["commit_id"] != "abc123" {
+                        return (422, json!({ "message": "commit_id is required" }));
+                    }
+                    let comment = &body["comments"][0];
+                    self.create_inline(&comment["path"], &comment["new_position"], &comment["body"])
+                }
+                _ => (404, json!({ "message": "Not Found" })),
+            }
+        }
+    }
+
+    /// 在随机端口上启动模拟服务，返回 API 地址。每个连接只处理一个请求。
+    async fn serve(state: Arc<Mutex<FakeForge>>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let state = Arc::clone(&state);
+                tokio::spawn(async move {
+                    let mut reader = BufReader::new(stream);
+                    let mut request_line = String::new();
+                    reader.read_line(&mut request_line).await.unwrap();
+                    let mut parts = request_line.split_whitespace();
+                    let method = parts.next().unwrap_or_default().to_string();
+                    let path = parts.next().unwrap_or_default().to_string();
+
+                    let mut length = 0;
+                    loop {
+                        let mut header = String::new();
+                        reader.read_line(&mut header).await.unwrap();
+                        if header.trim().is_empty() {
+                            break;
+                        }
+                        if let Some((name, value)) = header.split_once(':') {
+                            if name.eq_ignore_ascii_case("content-length") {
+                                length = value.trim().parse().unwrap();
+                            }
+                        }
+                    }
+                    let mut body = vec![0; length];
+                    reader.read_exact(&mut body).await.unwrap();
+                    let body: Value = serde_json::from_slice(&body).unwrap_or(Value::Null);
+
+                    let (status, reply) = state.lock().unwrap().handle(&method, &path, &body);
+                    let reply = reply.to_string();
+                    let response = format!(
+                        "HTTP/1.1 {} OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        status,
+                        reply.len(),
+                        reply
+                    );
+                    reader
+                        .into_inner()
+                        .write_all(response.as_bytes())
+                        .await
+                        .unwrap();
+                });
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    async fn config(state: &Arc<Mutex<FakeForge>>) -> Config {
+        let mut config = Config::load().unwrap();
+        config.forge_api = Some(serve(Arc::clone(state)).await);
+        config.forge_token = Some("token".to_string());
+        env::set_var("GITHUB_REPOSITORY", "o/r");
+        env::set_var("CI_PROJECT_ID", "42");
+        config
+    }
+
+    fn issue(severity: &str, perspective: &str, description: &str, location: &str) -> Issue {
+        Issue {
+            severity: severity.to_string(),
+            perspective: perspective.to_string(),
+            description: description.to_string(),
+            suggestion: String::new(),
+            location: location.to_string(),
+            patch: None,
+            models: Vec::new(),
+        }
+    }
+
+    fn review_result() -> AiCheckResult {
+        let mut result = AiCheckResult::local();
+        result.result = "NO".to_string();
+        result.list = vec![
+            issue("high", "security", "明文密码", "src/a.rs:2"),
+            issue("medium", "security", "SQL 拼接", "src/a.rs:3"),
+            // 同一次结果中重复的问题只评论一次
+            issue("high", "Security", "明文密码", "src/a.rs:2"),
+            // 不在 diff 新增行上的问题只出现在汇总评论里
+            issue("low", "style", "命名不一致", "src/a.rs:40"),
+            issue("low", "style", "缺少文档", "src/other.rs:1"),
+        ];
+        result
+    }
+
+    /// 第一次运行先发表汇总评论再发表行内评论；再次运行时原地更新汇总，已有的行内评论不再发表。
+    async fn summary_is_updated_and_inline_comments_are_not_repeated(
+        state: Arc<Mutex<FakeForge>>,
+        config: Config,
+        forge: impl Forge,
+    ) {
+        let result = review_result();
+
+        let first = post(&forge, &config, &result, DIFF, true).await.unwrap();
+        assert!(!first.updated);
+        assert_eq!(first.inline, 2);
+        assert_eq!(first.duplicates, 0);
+        assert!(first.failures.is_empty(), "{:?}", first.failures);
+        {
+            let state = state.lock().unwrap();
+            assert_eq!(state.events, ["summary", "inline", "inline"]);
+            assert_eq!(state.comments.len(), 1);
+            assert!(state.comments[0].1.contains(SUMMARY_MARKER));
+            let lines: Vec<(&str, u64)> = state
+                .inline
+                .iter()
+                .map(|(path, line, _)| (path.as_str(), *line))
+                .collect();
+            assert_eq!(lines, [("src/a.rs", 2), ("src/a.rs", 3)]);
+        }
+
+        let second = post(&forge, &config, &result, DIFF, true).await.unwrap();
+        assert!(second.updated);
+        assert_eq!(second.inline, 0);
+        // 重复的那条问题也命中已有评论
+        assert_eq!(second.duplicates, 3);
+        let state = state.lock().unwrap();
+        assert_eq!(state.comments.len(), 1);
+        assert_eq!(state.updates, 1);
+        assert_eq!(state.inline.len(), 2);
+        assert_eq!(state.events, ["summary", "inline", "inline", "summary"]);
+    }
+
+    /// 一条行内评论失败时记录下来，其他评论照常发表。
+    async fn failed_inline_comment_does_not_block_the_others(
+        state: Arc<Mutex<FakeForge>>,
+        config: Config,
+        forge: impl Forge,
+    ) {
+        state.lock().unwrap().reject_line = Some(2);
+
+        let published = post(&forge, &config, &review_result(), DIFF, true)
+            .await
+            .unwrap();
+        assert_eq!(published.inline, 1);
+        assert_eq!(published.failures.len(), 1);
+        assert!(format!("{:#}", published.failures[0]).contains("src/a.rs:2"));
+
+        let state = state.lock().unwrap();
+        assert_eq!(state.comments.len(), 1);
+        assert_eq!(state.inline.len(), 1);
+        assert_eq!(state.inline[0].1, 3);
+        assert_eq!(state.events, ["summary", "inline"]);
+    }
+
+    #[tokio::test]
+    async fn github_updates_summary_and_dedups_inline() {
+        let state = FakeForge::new(Kind::GitHub);
+        let config = config(&state).await;
+        let forge = GitHub::from_env(&config, Some(7)).unwrap();
+        summary_is_updated_and_inline_comments_are_not_repeated(state, config, forge).await;
+    }
+
+    #[tokio::test]
+    async fn github_collects_inline_failures() {
+        let state = FakeForge::new(Kind::GitHub);
+        let config = config(&state).await;
+        let forge = GitHub::from_env(&config, Some(7)).unwrap();
+        failed_inline_comment_does_not_block_the_others(state, config, forge).await;
+    }
+
+    #[tokio::test]
+    async fn gitlab_updates_summary_and_dedups_inline() {
+        let state = FakeForge::new(Kind::GitLab);
+        let config = config(&state).await;
+        let forge = GitLab::from_env(&config, Some(7)).unwrap();
+        summary_is_updated_and_inline_comments_are_not_repeated(state, config, forge).await;
+    }
+
+    #[tokio::test]
+    async fn gitlab_collects_inline_failures() {
+        let state = FakeForge::new(Kind::GitLab);
+        let config = config(&state).await;
+        let forge = GitLab::from_env(&config, Some(7)).unwrap();
+        failed_inline_comment_does_not_block_the_others(state, config, forge).await;
+    }
+
+    #[tokio::test]
+    async fn gitea_updates_summary_and_dedups_inline() {
+        let state = FakeForge::new(Kind::Gitea);
+        let config = config(&state).await;
+        let forge = Gitea::from_env(&config, Some(7)).unwrap();
+        summary_is_updated_and_inline_comments_are_not_repeated(state, config, forge).await;
+    }
+
+    #[tokio::test]
+    async fn gitea_collects_inline_failures() {
+        let state = FakeForge::new(Kind::Gitea);
+        let config = config(&state).await;
+        let forge = Gitea::from_env(&config, Some(7)).unwrap();
+        failed_inline_comment_does_not_block_the_others(state, config, forge).await;
+    }
+}
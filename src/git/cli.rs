@@ -0,0 +1,191 @@
+use super::{path_from_bytes, GitBackend};
+use crate::i18n::Msg;
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// 通过调用 `git` 命令行实现的后端，所有平台都可用。
+pub struct CliBackend;
+
+/// 执行 git 命令并返回原始 stdout（不做 UTF-8 转换）。
+fn run(cmd: &mut Command, what: &str) -> Result<Vec<u8>> {
+    let output = cmd
+        .output()
+        .with_context(|| format!("Failed to execute {}", what))?;
+
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "Git command failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(output.stdout)
+}
+
+impl CliBackend {
+    /// 判断某个修订是否存在（例如 `CHERRY_PICK_HEAD`）。
+    pub fn rev_exists(&self, rev: &str) -> bool {
+        Command::new("git")
+            .args(["rev-parse", "-q", "--verify", rev])
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+
+    /// 当前分支名（`git symbolic-ref --short HEAD`），分离 HEAD 时返回错误。
+    pub fn current_branch(&self) -> Result<String> {
+        let stdout = run(
+            Command::new("git").args(["symbolic-ref", "--short", "-q", "HEAD"]),
+            "git symbolic-ref",
+        )?;
+        Ok(String::from_utf8_lossy(&stdout).trim().to_string())
+    }
+
+    /// 将当前索引写成树对象并返回其 id。
+    pub fn write_tree(&self) -> Result<String> {
+        let stdout = run(Command::new("git").arg("write-tree"), "git write-tree")?;
+        Ok(String::from_utf8_lossy(&stdout).trim().to_string())
+    }
+
+    /// `git diff --cc -U0 <result> <parent>...`：只保留与所有父提交都不同的块，
+    /// 即手工解决冲突的部分。libgit2 不支持 combined diff，所以仅由命令行实现。
+    pub fn combined_diff(&self, result: &str, parents: &[&str], files: &[PathBuf]) -> Result<Vec<u8>> {
+        run(
+            Command::new("git")
+                .args(["diff", "--cc", "-U0", result])
+                .args(parents)
+                .arg("--")
+                .args(files),
+            "git diff --cc",
+        )
+    }
+
+    /// 修订范围（如 `base...head`）中新增、复制或修改的文件。
+    pub fn range_files(&self, range: &str) -> Result<Vec<PathBuf>> {
+        let stdout = run(
+            Command::new("git").args(["diff", "--name-only", "-z", "--diff-filter=ACM", range]),
+            "git diff --name-only",
+        )?;
+
+        Ok(stdout
+            .split(|b| *b == 0)
+            .filter(|name| !name.is_empty())
+            .map(path_from_bytes)
+            .collect())
+    }
+
+    /// 指定文件在修订范围中的 `-U0` diff。
+    pub fn range_diff(&self, range: &str, files: &[PathBuf]) -> Result<Vec<u8>> {
+        run(
+            Command::new("git")
+                .args(["diff", "-U0", range, "--"])
+                .args(files),
+            "git diff for revision range",
+        )
+    }
+
+    /// 读取某个修订中的文件内容（`git cat-file blob <rev>:<path>`），文件不存在时返回 None。
+    pub fn show_file(&self, rev: &str, path: &str) -> Result<Option<Vec<u8>>> {
+        let object = format!("{}:{}", rev, path);
+        if !self.rev_exists(&object) {
+            return Ok(None);
+        }
+        run(
+            Command::new("git").args(["cat-file", "blob", &object]),
+            "git cat-file",
+        )
+        .map(Some)
+    }
+
+    /// 通过标准输入把补丁交给 `git apply`，附加参数决定检查或应用到哪里。
+    ///
+    /// 模型只看到 `-U0` 的 diff，生成的补丁常常行数统计不准、没有上下文行，所以总是加上
+    /// `--recount --unidiff-zero`。这样 git 不再用上下文核对位置，调用方需要自己确认补丁
+    /// 修改的是问题所在的行（见 `fix::validate_patches`）。
+    pub fn apply_patch(&self, patch: &str, args: &[&str]) -> Result<()> {
+        let mut child = Command::new("git")
+            .args(["apply", "--recount", "--unidiff-zero"])
+            .args(args)
+            .arg("-")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("Failed to execute git apply")?;
+
+        child
+            .stdin
+            .take()
+            .context(Msg::GitApplyStdinFailed.text())?
+            .write_all(patch.as_bytes())?;
+
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(anyhow::anyhow!(
+                "Git command failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl GitBackend for CliBackend {
+    fn name(&self) -> &'static str {
+        "cli"
+    }
+
+    fn git_dir(&self) -> Result<PathBuf> {
+        let stdout = run(
+            Command::new("git").args(["rev-parse", "--absolute-git-dir"]),
+            "git rev-parse --absolute-git-dir",
+        )?;
+
+        // 去掉末尾换行，但保留路径中的其他字节
+        let trimmed = stdout.strip_suffix(b"\n").unwrap_or(&stdout);
+        Ok(path_from_bytes(trimmed))
+    }
+
+    fn work_dir(&self) -> Result<PathBuf> {
+        let stdout = run(
+            Command::new("git").args(["rev-parse", "--show-toplevel"]),
+            "git rev-parse --show-toplevel",
+        )?;
+
+        let trimmed = stdout.strip_suffix(b"\n").unwrap_or(&stdout);
+        Ok(path_from_bytes(trimmed))
+    }
+
+    fn staged_files(&self) -> Result<Vec<PathBuf>> {
+        // 使用 -z 输出，避免 git 对非 ASCII 路径进行转义
+        let stdout = run(
+            Command::new("git").args(["diff", "--cached", "--name-only", "-z", "--diff-filter=ACM"]),
+            "git diff --cached --name-only",
+        )?;
+
+        Ok(stdout
+            .split(|b| *b == 0)
+            .filter(|name| !name.is_empty())
+            .map(path_from_bytes)
+            .collect())
+    }
+
+    fn staged_diff(&self, files: &[PathBuf]) -> Result<Vec<u8>> {
+        // git diff --cached -U0 -- file1 file2 ...
+        run(
+            Command::new("git")
+                .args(["diff", "--cached", "-U0", "--"])
+                .args(files),
+            "git diff for specific files",
+        )
+    }
+
+    fn staged_stat(&self) -> Result<Vec<u8>> {
+        run(
+            Command::new("git").args(["diff", "--cached", "--stat"]),
+            "git diff --cached --stat",
+        )
+    }
+}
@@ -0,0 +1,112 @@
+use super::{path_from_bytes, GitBackend};
+use crate::i18n::Msg;
+use anyhow::{Context, Result};
+use git2::{Delta, DiffFormat, DiffOptions, DiffStatsFormat, ErrorCode, Repository};
+use std::path::PathBuf;
+
+/// 基于 libgit2 的进程内后端，直接读取索引计算 diff，无需启动 git 子进程。
+pub struct Libgit2Backend {
+    repo: Repository,
+}
+
+impl Libgit2Backend {
+    pub fn open() -> Result<Self> {
+        let repo = Repository::open_from_env().context("Failed to open git repository")?;
+        Ok(Self { repo })
+    }
+
+    fn index_diff(&self, files: &[PathBuf], context_lines: u32) -> Result<git2::Diff<'_>> {
+        // 首次提交时 HEAD 尚不存在，此时与空树比较；其他错误（如引用损坏）不能当作空仓库
+        let head_tree = match self.repo.head() {
+            Ok(head) => Some(head.peel_to_tree().context("Failed to resolve HEAD tree")?),
+            Err(e) if matches!(e.code(), ErrorCode::UnbornBranch | ErrorCode::NotFound) => None,
+            Err(e) => return Err(e).context("Failed to read HEAD"),
+        };
+
+        let mut opts = DiffOptions::new();
+        opts.context_lines(context_lines);
+        for file in files {
+            opts.pathspec(file);
+        }
+        if !files.is_empty() {
+            opts.disable_pathspec_match(true);
+        }
+
+        let index = self.repo.index().context("Failed to read git index")?;
+        let mut diff = self
+            .repo
+            .diff_tree_to_index(head_tree.as_ref(), Some(&index), Some(&mut opts))
+            .context("Failed to diff index against HEAD")?;
+
+        // git diff 默认检测重命名（diff.renames），不做检测时重命名会变成删除 + 新增，
+        // 与命令行后端的 --diff-filter=ACM 结果不一致
+        diff.find_similar(None)
+            .context("Failed to detect renames")?;
+        Ok(diff)
+    }
+}
+
+/// 与 `--diff-filter=ACM` 保持一致：只保留新增、复制和修改的文件。
+fn is_acm(delta: &git2::DiffDelta<'_>) -> bool {
+    matches!(
+        delta.status(),
+        Delta::Added | Delta::Copied | Delta::Modified
+    )
+}
+
+impl GitBackend for Libgit2Backend {
+    fn name(&self) -> &'static str {
+        "libgit2"
+    }
+
+    fn git_dir(&self) -> Result<PathBuf> {
+        Ok(self.repo.path().to_path_buf())
+    }
+
+    fn work_dir(&self) -> Result<PathBuf> {
+        self.repo
+            .workdir()
+            .map(|p| p.to_path_buf())
+            .context(Msg::BareRepository.text())
+    }
+
+    fn staged_files(&self) -> Result<Vec<PathBuf>> {
+        let diff = self.index_diff(&[], 0)?;
+
+        Ok(diff
+            .deltas()
+            .filter(is_acm)
+            .filter_map(|d| d.new_file().path_bytes().map(path_from_bytes))
+            .collect())
+    }
+
+    fn staged_diff(&self, files: &[PathBuf]) -> Result<Vec<u8>> {
+        let diff = self.index_diff(files, 0)?;
+
+        let mut out = Vec::new();
+        diff.print(DiffFormat::Patch, |delta, _hunk, line| {
+            if !is_acm(&delta) {
+                return true;
+            }
+            if matches!(line.origin(), '+' | '-' | ' ') {
+                out.push(line.origin() as u8);
+            }
+            out.extend_from_slice(line.content());
+            true
+        })
+        .context("Failed to render staged diff")?;
+
+        Ok(out)
+    }
+
+    fn staged_stat(&self) -> Result<Vec<u8>> {
+        let stats = self
+            .index_diff(&[], 0)?
+            .stats()
+            .context("Failed to compute diff stats")?;
+        let buf = stats
+            .to_buf(DiffStatsFormat::FULL, 80)
+            .context("Failed to render diff stats")?;
+        Ok(buf.to_vec())
+    }
+}
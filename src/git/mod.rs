@@ -0,0 +1,356 @@
+mod cli;
+#[cfg(feature = "libgit2")]
+mod libgit2;
+
+use crate::config::Config;
+use crate::filter;
+use crate::i18n::{Locale, Msg};
+use anyhow::{Context, Result};
+use colored::*;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+pub use cli::CliBackend;
+#[cfg(feature = "libgit2")]
+pub use libgit2::Libgit2Backend;
+
+/// Git 操作的后端抽象。
+///
+/// 默认实现通过 `git` 命令行完成；启用 `libgit2` feature 后可以在进程内直接读取索引。
+pub trait GitBackend {
+    fn name(&self) -> &'static str;
+
+    /// 仓库的 `.git` 目录（绝对路径）。
+    fn git_dir(&self) -> Result<PathBuf>;
+
+    /// 工作区根目录（绝对路径）。
+    fn work_dir(&self) -> Result<PathBuf>;
+
+    /// 暂存区中新增、复制或修改的文件（等价于 `--diff-filter=ACM`）。
+    fn staged_files(&self) -> Result<Vec<PathBuf>>;
+
+    /// 指定文件在暂存区与 HEAD 之间的 `-U0` diff，返回原始字节。
+    fn staged_diff(&self, files: &[PathBuf]) -> Result<Vec<u8>>;
+
+    /// 暂存区变更的统计摘要（等价于 `git diff --cached --stat`）。
+    fn staged_stat(&self) -> Result<Vec<u8>>;
+}
+
+/// 进程内后端失败时自动回退到命令行后端。
+#[cfg(feature = "libgit2")]
+struct Fallback {
+    primary: Box<dyn GitBackend>,
+    cli: CliBackend,
+}
+
+#[cfg(feature = "libgit2")]
+impl Fallback {
+    fn try_both<T>(&self, op: impl Fn(&dyn GitBackend) -> Result<T>) -> Result<T> {
+        match op(self.primary.as_ref()) {
+            Ok(v) => Ok(v),
+            Err(e) => {
+                eprintln!(
+                    "{} {}",
+                    Msg::Warning.text().yellow(),
+                    Msg::BackendFallback.fill(&[
+                        ("backend", self.primary.name().to_string()),
+                        ("error", e.to_string()),
+                    ])
+                );
+                op(&self.cli)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "libgit2")]
+impl GitBackend for Fallback {
+    fn name(&self) -> &'static str {
+        self.primary.name()
+    }
+
+    fn git_dir(&self) -> Result<PathBuf> {
+        self.try_both(|b| b.git_dir())
+    }
+
+    fn work_dir(&self) -> Result<PathBuf> {
+        self.try_both(|b| b.work_dir())
+    }
+
+    fn staged_files(&self) -> Result<Vec<PathBuf>> {
+        self.try_both(|b| b.staged_files())
+    }
+
+    fn staged_diff(&self, files: &[PathBuf]) -> Result<Vec<u8>> {
+        self.try_both(|b| b.staged_diff(files))
+    }
+
+    fn staged_stat(&self) -> Result<Vec<u8>> {
+        self.try_both(|b| b.staged_stat())
+    }
+}
+
+/// 根据 `AI_CHECK_GIT_BACKEND`（auto/cli/libgit2）选择后端。
+pub fn backend(config: &Config) -> Box<dyn GitBackend> {
+    match config.git_backend.as_str() {
+        "cli" => Box::new(CliBackend),
+        #[cfg(feature = "libgit2")]
+        _ => match Libgit2Backend::open() {
+            Ok(primary) => Box::new(Fallback {
+                primary: Box::new(primary),
+                cli: CliBackend,
+            }),
+            Err(_) => Box::new(CliBackend),
+        },
+        #[cfg(not(feature = "libgit2"))]
+        other => {
+            if other == "libgit2" {
+                eprintln!("{} {}", Msg::Warning.text().yellow(), Msg::Libgit2Disabled);
+            }
+            Box::new(CliBackend)
+        }
+    }
+}
+
+/// 仓库当前所处的多步操作状态。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepoState {
+    Clean,
+    Merge,
+    Rebase,
+    CherryPick,
+    Revert,
+}
+
+impl RepoState {
+    pub fn label(&self) -> &'static str {
+        match self {
+            RepoState::Clean => Msg::StateClean,
+            RepoState::Merge => Msg::StateMerge,
+            RepoState::Rebase => Msg::StateRebase,
+            RepoState::CherryPick => Msg::StateCherryPick,
+            RepoState::Revert => Msg::StateRevert,
+        }
+        .text()
+    }
+
+    /// 冲突解决时与 HEAD 一起作为“另一方”参与 combined diff 的修订。
+    /// revert 的结果对应被还原提交的父提交，所以取 `REVERT_HEAD^`。
+    fn other_side(&self) -> Option<&'static str> {
+        match self {
+            RepoState::Clean => None,
+            RepoState::Merge => Some("MERGE_HEAD"),
+            RepoState::Rebase => Some("REBASE_HEAD"),
+            RepoState::CherryPick => Some("CHERRY_PICK_HEAD"),
+            RepoState::Revert => Some("REVERT_HEAD^"),
+        }
+    }
+}
+
+/// 通过 `.git` 目录中的标记文件判断当前是否处于 merge/rebase/cherry-pick/revert 中。
+pub fn repo_state(config: &Config) -> Result<RepoState> {
+    let git_dir = backend(config).git_dir()?;
+
+    // rebase 重放提交时也可能留下 CHERRY_PICK_HEAD，因此先判断 rebase
+    let state = if git_dir.join("rebase-merge").is_dir()
+        || git_dir.join("rebase-apply").is_dir()
+        || git_dir.join("REBASE_HEAD").exists()
+    {
+        RepoState::Rebase
+    } else if git_dir.join("MERGE_HEAD").exists() {
+        RepoState::Merge
+    } else if git_dir.join("CHERRY_PICK_HEAD").exists() {
+        RepoState::CherryPick
+    } else if git_dir.join("REVERT_HEAD").exists() {
+        RepoState::Revert
+    } else {
+        RepoState::Clean
+    };
+
+    Ok(state)
+}
+
+pub fn get_staged_diff(config: &Config) -> Result<String> {
+    let backend = backend(config);
+
+    // 1. 获取暂存区中需要检查的文件
+    let files = monitored_files(config, backend.as_ref())?;
+
+    if files.is_empty() {
+        return Ok(String::new());
+    }
+
+    // 2. 获取过滤后的文件的 diff 内容
+    // 非 UTF-8 的内容会被替换为 U+FFFD，而不是直接报错
+    let diff = backend
+        .staged_diff(&files)
+        .with_context(|| Msg::StagedDiffFailed.fill(&[("backend", backend.name().to_string())]))?;
+    Ok(String::from_utf8_lossy(&diff).into_owned())
+}
+
+/// 暂存区中全部新增/修改文件的 diff，不按扩展名和排除规则过滤，供密钥扫描使用。
+pub fn get_staged_diff_unfiltered(config: &Config) -> Result<String> {
+    let backend = backend(config);
+    let files = backend
+        .staged_files()
+        .with_context(|| Msg::StagedFilesFailed.fill(&[("backend", backend.name().to_string())]))?;
+    if files.is_empty() {
+        return Ok(String::new());
+    }
+
+    let diff = backend
+        .staged_diff(&files)
+        .with_context(|| Msg::StagedDiffFailed.fill(&[("backend", backend.name().to_string())]))?;
+    Ok(String::from_utf8_lossy(&diff).into_owned())
+}
+
+/// 暂存区变更摘要，用于提交信息审查等不需要完整 diff 的场景。
+pub fn get_staged_summary(config: &Config) -> Result<String> {
+    let stat = backend(config).staged_stat()?;
+    Ok(String::from_utf8_lossy(&stat).into_owned())
+}
+
+/// 附加在 combined diff 前面，帮助模型理解双列前缀的含义。
+fn combined_diff_note(config: &Config) -> &'static str {
+    match config.prompt_lang {
+        Locale::ZhCn => "[以下是 git diff --cc 格式的冲突解决结果：每行前两列分别对应 HEAD 和另一方，\
+'++' 表示手工解决冲突时新写入的行，'-' 表示从对应一方删除的行。只需审查这些手工修改。]",
+        Locale::En => "[The following is a conflict resolution in git diff --cc format: the first two columns \
+of each line refer to HEAD and the other side, '++' marks lines newly written while resolving the conflict \
+and '-' marks lines removed from that side. Review only these manual changes.]",
+    }
+}
+
+/// 只获取冲突解决部分的 diff：暂存结果与 HEAD、另一方都不同的块。
+///
+/// 如果当前状态没有可比较的另一方（例如 rebase 中的 `edit` 停顿），返回空字符串。
+pub fn get_resolution_diff(config: &Config, state: RepoState) -> Result<String> {
+    let cli = CliBackend;
+    let other = match state.other_side() {
+        Some(rev) if cli.rev_exists(rev) => rev,
+        _ => return Ok(String::new()),
+    };
+
+    let files = monitored_files(config, backend(config).as_ref())?;
+    if files.is_empty() {
+        return Ok(String::new());
+    }
+
+    let tree = cli.write_tree()?;
+    let diff = cli.combined_diff(&tree, &["HEAD", other], &files)?;
+    if diff.is_empty() {
+        // 所有块都原样取自某一方，没有需要审查的手工修改
+        return Ok(String::new());
+    }
+
+    Ok(format!(
+        "{}\n\n{}",
+        combined_diff_note(config),
+        String::from_utf8_lossy(&diff)
+    ))
+}
+
+/// `base...head` 的 diff：head 相对两者合并基点的变更，即 PR/MR 引入的内容。
+///
+/// 只在 CI 中使用，由命令行后端实现。不按扩展名过滤：密钥扫描需要看到全部文件，
+/// 审查范围在扫描之后再筛选。
+pub fn get_range_diff(base: &str, head: &str) -> Result<String> {
+    let cli = CliBackend;
+    let range = format!("{}...{}", base, head);
+
+    let files = cli
+        .range_files(&range)
+        .with_context(|| Msg::RangeFilesFailed.fill(&[("range", range.clone())]))?;
+    if files.is_empty() {
+        return Ok(String::new());
+    }
+
+    let diff = cli
+        .range_diff(&range, &files)
+        .with_context(|| Msg::RangeDiffFailed.fill(&[("range", range.clone())]))?;
+    Ok(String::from_utf8_lossy(&diff).into_owned())
+}
+
+/// 读取仓库中影响审查方式的文件（提示词模板、项目上下文、误报基线），`path` 相对仓库根目录。
+///
+/// 设置了 `config.trusted_rev`（CI 中为 PR 的目标分支）时从该修订读取，PR 无法通过修改
+/// 这些文件改写对自己的审查；否则读取工作区。返回内容和用于提示的来源，文件不存在时返回 None。
+pub fn read_repo_file(config: &Config, path: &str) -> Result<Option<(String, PathBuf)>> {
+    if let Some(rev) = &config.trusted_rev {
+        let content = CliBackend
+            .show_file(rev, path)
+            .with_context(|| Msg::ReadFileFailed.fill(&[("path", format!("{}:{}", rev, path))]))?;
+        return Ok(content.map(|bytes| {
+            (
+                String::from_utf8_lossy(&bytes).into_owned(),
+                PathBuf::from(format!("{}:{}", rev, path)),
+            )
+        }));
+    }
+
+    // 不在 git 仓库中（`review` 任意文件）时相对当前目录查找
+    let full = backend(config).work_dir().unwrap_or_default().join(path);
+    match fs::read_to_string(&full) {
+        Ok(content) => Ok(Some((content, full))),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => {
+            Err(e).with_context(|| Msg::ReadFileFailed.fill(&[("path", format!("{:?}", full))]))
+        }
+    }
+}
+
+/// 当前分支名。CI 中通常是分离 HEAD，此时使用 CI 提供的源分支名；都没有时返回 None。
+pub fn current_branch() -> Option<String> {
+    CliBackend
+        .current_branch()
+        .ok()
+        .filter(|branch| !branch.is_empty())
+        .or_else(|| {
+            [
+                "GITHUB_HEAD_REF",
+                "CI_MERGE_REQUEST_SOURCE_BRANCH_NAME",
+                "CI_COMMIT_REF_NAME",
+            ]
+            .iter()
+            .find_map(|name| std::env::var(name).ok().filter(|v| !v.trim().is_empty()))
+        })
+}
+
+/// 检查补丁能否干净地应用到暂存区中的文件（`git apply --cached --check`）。
+pub fn check_patch(patch: &str) -> Result<()> {
+    CliBackend.apply_patch(patch, &["--cached", "--check"])
+}
+
+/// 把补丁同时应用到工作区和暂存区（`git apply --index`）。
+///
+/// 工作区中的文件与暂存区不一致时 git 会拒绝应用，不会覆盖未暂存的修改。
+pub fn apply_patch(patch: &str) -> Result<()> {
+    CliBackend.apply_patch(patch, &["--index"])
+}
+
+/// 暂存区中新增/修改且扩展名在配置范围内的文件。
+fn monitored_files(config: &Config, backend: &dyn GitBackend) -> Result<Vec<PathBuf>> {
+    let files = backend
+        .staged_files()
+        .with_context(|| Msg::StagedFilesFailed.fill(&[("backend", backend.name().to_string())]))?;
+
+    // 根据配置文件中指定的文件扩展名和排除规则过滤
+    Ok(files
+        .into_iter()
+        .filter(|file| filter::is_monitored(config, file))
+        .collect())
+}
+
+/// 将 git 输出中的原始路径字节转换为 `PathBuf`，在 Unix 上保留非 UTF-8 字节。
+fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        PathBuf::from(std::ffi::OsStr::from_bytes(bytes))
+    }
+    #[cfg(not(unix))]
+    {
+        PathBuf::from(String::from_utf8_lossy(bytes).into_owned())
+    }
+}
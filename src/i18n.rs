@@ -0,0 +1,371 @@
+use crate::template;
+use std::env;
+use std::sync::OnceLock;
+
+/// 界面语言。提示词模板和终端输出都按它选择。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    ZhCn,
+    En,
+}
+
+impl Locale {
+    /// 解析 `zh`、`zh-CN`、`en_US.UTF-8` 这样的语言标识；其他非中文语言使用英文。
+    /// `C`/`POSIX`（容器中常见的默认值）和无法识别的值返回 None。
+    pub fn parse(value: &str) -> Option<Locale> {
+        let lang = value
+            .trim()
+            .split(['.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        match lang.as_str() {
+            "" | "c" | "posix" | "auto" => None,
+            l if l.starts_with("zh") => Some(Locale::ZhCn),
+            l if l.len() >= 2 && l.chars().all(|c| c.is_ascii_alphabetic() || c == '_' || c == '-') => {
+                Some(Locale::En)
+            }
+            _ => None,
+        }
+    }
+
+    /// 按 `AI_CHECK_UI_LANG`、`LC_ALL`、`LC_MESSAGES`、`LANG` 的顺序确定语言，默认中文。
+    pub fn from_env() -> Locale {
+        ["AI_CHECK_UI_LANG", "LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|key| env::var(key).ok())
+            .find_map(|value| Locale::parse(&value))
+            .unwrap_or(Locale::ZhCn)
+    }
+
+    /// 未设置 `AI_CHECK_LANGUAGE` 时模型描述问题使用的语言。
+    pub fn default_language(self) -> &'static str {
+        match self {
+            Locale::ZhCn => "chinese",
+            Locale::En => "english",
+        }
+    }
+}
+
+static LOCALE: OnceLock<Locale> = OnceLock::new();
+
+/// 记下配置中的界面语言，之后的输出都使用它。
+pub fn set(locale: Locale) {
+    let _ = LOCALE.set(locale);
+}
+
+/// 当前界面语言。配置加载之前（安装、更新等命令）直接从环境变量判断。
+pub fn locale() -> Locale {
+    LOCALE.get().copied().unwrap_or_else(Locale::from_env)
+}
+
+/// 定义消息编号及各语言的文字，`{{name}}` 为占位符。
+/// 只在部分构建中使用的消息可以加上 `#[cfg(...)]`。
+macro_rules! messages {
+    ($($(#[$meta:meta])* $name:ident { zh: $zh:literal, en: $en:literal })*) => {
+        /// 终端输出的消息编号。
+        #[derive(Debug, Clone, Copy)]
+        pub enum Msg {
+            $($(#[$meta])* $name,)*
+        }
+
+        impl Msg {
+            /// 当前语言的消息文字。
+            pub fn text(self) -> &'static str {
+                match locale() {
+                    Locale::ZhCn => match self {
+                        $($(#[$meta])* Msg::$name => $zh,)*
+                    },
+                    Locale::En => match self {
+                        $($(#[$meta])* Msg::$name => $en,)*
+                    },
+                }
+            }
+        }
+    };
+}
+
+impl Msg {
+    /// 用参数替换消息中的占位符。
+    pub fn fill(self, vars: &[(&str, String)]) -> String {
+        template::render(self.text(), vars)
+    }
+}
+
+impl std::fmt::Display for Msg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.text())
+    }
+}
+
+messages! {
+    // 通用
+    Warning { zh: "警告:", en: "Warning:" }
+    Error { zh: "错误:", en: "Error:" }
+    Processing { zh: "处理中:", en: "Processing:" }
+    Approved { zh: "✅ 代码已通过！", en: "✅ Code approved!" }
+    Rejected { zh: "❌ 代码被拒绝，发现严重问题。", en: "❌ Code rejected: serious issues found." }
+    Builtin { zh: "内置", en: "built-in" }
+    ReadFileFailed { zh: "读取 {{path}} 失败", en: "Failed to read {{path}}" }
+    ParseFileFailed { zh: "解析 {{path}} 失败", en: "Failed to parse {{path}}" }
+    WriteFileFailed { zh: "写入 {{path}} 失败", en: "Failed to write {{path}}" }
+    Done { zh: "完成:", en: "Done:" }
+
+    EnvVarMissing { zh: "未设置 {{name}}", en: "{{name}} is not set" }
+    PathNotFound { zh: "路径不存在: {{path}}", en: "Path does not exist: {{path}}" }
+    ReadDirFailed { zh: "读取目录失败: {{path}}", en: "Failed to read directory: {{path}}" }
+    SuggestionLabel { zh: "建议:", en: "Suggestion:" }
+
+
+    // 配置
+    ConfigError { zh: "配置错误:", en: "Configuration error:" }
+    ConfigHint { zh: "请检查您的 .env 文件或环境变量。", en: "Please check your .env file or environment variables." }
+    ConfigLoaded { zh: "📄 已加载配置文件: {{path}}", en: "📄 Loaded configuration from: {{path}}" }
+    ConfigLoadedCwd { zh: "📄 已加载当前目录的配置文件 (.env)", en: "📄 Loaded configuration from current directory (.env)" }
+    EndpointKeyMissing { zh: "{{var}} 引用的环境变量 {{name}} 未设置", en: "environment variable {{name}} referenced by {{var}} is not set" }
+    ChunkSizeDeprecated { zh: "AI_CHECK_MAX_CHUNK_SIZE 已弃用（按字符计），按约 4 个字符 1 个 Token 换算为 AI_CHECK_MAX_CHUNK_TOKENS={{tokens}}，请改用 AI_CHECK_MAX_CHUNK_TOKENS", en: "AI_CHECK_MAX_CHUNK_SIZE is deprecated (it counts characters); converted to AI_CHECK_MAX_CHUNK_TOKENS={{tokens}} at about 4 characters per token. Please use AI_CHECK_MAX_CHUNK_TOKENS instead" }
+    ChunkSizeIgnored { zh: "AI_CHECK_MAX_CHUNK_SIZE 已弃用，同时设置了 AI_CHECK_MAX_CHUNK_TOKENS，忽略前者", en: "AI_CHECK_MAX_CHUNK_SIZE is deprecated and ignored because AI_CHECK_MAX_CHUNK_TOKENS is set" }
+    PolicyInvalid { zh: "{{var}} 的取值 {{value}} 无效，可选 skip/review/conflicts", en: "Invalid value {{value}} for {{var}}; expected skip/review/conflicts" }
+    TicketPatternInvalid { zh: "AI_CHECK_COMMIT_TICKET_PATTERN 不是有效的正则表达式: {{error}}", en: "AI_CHECK_COMMIT_TICKET_PATTERN is not a valid regular expression: {{error}}" }
+    ExtraBodyInvalid { zh: "AI_CHECK_EXTRA_BODY 必须是 JSON 对象: {{error}}", en: "AI_CHECK_EXTRA_BODY must be a JSON object: {{error}}" }
+
+    // 仓库状态
+    StateClean { zh: "普通提交", en: "regular commit" }
+    StateMerge { zh: "合并 (merge)", en: "merge" }
+    StateRebase { zh: "变基 (rebase)", en: "rebase" }
+    StateCherryPick { zh: "拣选 (cherry-pick)", en: "cherry-pick" }
+    StateRevert { zh: "还原 (revert)", en: "revert" }
+
+    // check / review / ci
+    CheckStarted { zh: "AI Git Pre-Commit 检查已启动...", en: "AI Git Pre-Commit check started..." }
+    ReviewStarted { zh: "AI 代码审查已启动...", en: "AI code review started..." }
+    MergeSkipped { zh: "检测到合并操作。跳过 AI 检查以避免分析大量合并代码。", en: "Merge in progress. Skipping the AI check to avoid analysing large amounts of merged code." }
+    OperationSkipped { zh: "检测到{{operation}}操作。跳过 AI 检查以避免重复审查已审查过的代码。", en: "{{operation}} in progress. Skipping the AI check to avoid reviewing already reviewed code again." }
+    ConflictsOnly { zh: "检测到{{operation}}操作，仅审查冲突解决部分。", en: "{{operation}} in progress, reviewing conflict resolutions only." }
+    GitError { zh: "Git 错误:", en: "Git error:" }
+    NoStagedChanges { zh: "未发现暂存的更改。跳过检查。", en: "No staged changes. Skipping the check." }
+    ReadFilesFailed { zh: "读取文件失败:", en: "Failed to read files:" }
+    NoFilesToReview { zh: "在指定路径中未找到需要审查的文件。", en: "No files to review under the given paths." }
+    ReviewRange { zh: "审查范围:", en: "Review range:" }
+    DiffFailed { zh: "获取 diff 失败:", en: "Failed to get diff:" }
+    NoChanges { zh: "没有需要检查的变更。", en: "No changes to check." }
+    NoMonitoredChanges { zh: "变更中没有需要审查的文件。跳过检查。", en: "No monitored files among the changes. Skipping the check." }
+    Baseline { zh: "基线:", en: "Baseline:" }
+    BaselineSuppressed { zh: "已忽略 {{count}} 个基线中的问题。", en: "Ignored {{count}} issue(s) listed in the baseline." }
+    BaselineError { zh: "基线文件错误:", en: "Baseline file error:" }
+    BaselineParseFailed { zh: "解析基线文件失败: {{path}}", en: "Failed to parse the baseline file: {{path}}" }
+    BaselineDirFailed { zh: "创建基线目录失败", en: "Failed to create the baseline directory" }
+    BaselineWriteFailed { zh: "写入基线文件失败: {{path}}", en: "Failed to write the baseline file: {{path}}" }
+
+    CodeQualityWritten { zh: "已写入 Code Quality 报告:", en: "Code Quality report written to:" }
+    PrComment { zh: "PR 评论:", en: "PR comments:" }
+    PrCommentPosted { zh: "已发表 {{forge}} 汇总评论，新增 {{inline}} 条行内评论（{{duplicates}} 条已存在）。", en: "Posted the {{forge}} summary comment and {{inline}} new inline comment(s) ({{duplicates}} already present)." }
+    PrCommentUpdated { zh: "已更新 {{forge}} 汇总评论，新增 {{inline}} 条行内评论（{{duplicates}} 条已存在）。", en: "Updated the {{forge}} summary comment and posted {{inline}} new inline comment(s) ({{duplicates}} already present)." }
+    PrCommentFailed { zh: "发表 PR 评论失败:", en: "Failed to post PR comments:" }
+    CiThresholdFailed { zh: "❌ 发现达到阈值 ({{threshold}}) 的问题。", en: "❌ Found issues at or above the threshold ({{threshold}})." }
+    SecretsRedacted { zh: "检测到 {{count}} 处疑似密钥，已在发送前脱敏。", en: "Detected {{count}} possible secret(s); redacted before sending." }
+    ProjectContext { zh: "项目上下文:", en: "Project context:" }
+    ContextSkipped { zh: "项目上下文已达到 AI_CHECK_CONTEXT_MAX_TOKENS ({{max}})，忽略 {{path}}", en: "Project context reached AI_CHECK_CONTEXT_MAX_TOKENS ({{max}}); ignoring {{path}}" }
+    ContextTruncated { zh: "项目上下文超出 AI_CHECK_CONTEXT_MAX_TOKENS ({{max}})，{{path}} 已截断", en: "Project context exceeds AI_CHECK_CONTEXT_MAX_TOKENS ({{max}}); truncated {{path}}" }
+
+    TemplateError { zh: "提示词模板错误:", en: "Prompt template error:" }
+    AnalyzingChanges { zh: "正在分析约 {{tokens}} tokens 的代码变更（共 {{chunks}} 块，每块上限 {{budget}} tokens）...", en: "Analysing about {{tokens}} tokens of changes ({{chunks}} chunk(s), up to {{budget}} tokens each)..." }
+    LocalBlocked { zh: "本地检查发现严重问题，跳过 AI 检查。", en: "Local checks found serious issues. Skipping the AI check." }
+    ReviewingChunk { zh: "正在审查第 {{n}}/{{total}} 块...", en: "Reviewing chunk {{n}}/{{total}}..." }
+    ProgressWaiting { zh: "等待 AI 响应...", en: "Waiting for the AI response..." }
+    ProgressVoting { zh: "等待 {{count}} 个模型响应...", en: "Waiting for {{count}} models to respond..." }
+    ProgressChunks { zh: "已完成 {{done}}/{{total}} 块", en: "{{done}}/{{total}} chunks done" }
+    ProgressTokens { zh: "已接收约 {{tokens}} tokens", en: "received about {{tokens}} tokens" }
+    AiCheckFailed { zh: "AI 检查失败:", en: "AI check failed:" }
+    AllResolved { zh: "✅ 所有阻塞问题均已处理，继续提交。", en: "✅ All blocking issues handled, continuing with the commit." }
+    CommitAborted { zh: "提交已中止。", en: "Commit aborted." }
+    InteractiveFailed { zh: "交互处理失败:", en: "Interactive review failed:" }
+
+    // 审查报告
+    AnalysisResult { zh: "分析结果:", en: "Analysis result:" }
+    ReviewModels { zh: "审查模型:", en: "Models:" }
+    Triage { zh: "风险分流:", en: "Risk triage:" }
+    TriageChunk { zh: "第 {{n}} 块", en: "Chunk {{n}}" }
+    DeepReview { zh: "（深度审查）", en: " (deep review)" }
+    TokenUsage { zh: "Token 使用:", en: "Token usage:" }
+    TokenTotals { zh: "输入: {{input}} tokens, 输出: {{output}} tokens, 总计: {{total}} tokens", en: "input: {{input}} tokens, output: {{output}} tokens, total: {{total}} tokens" }
+    StageTokens { zh: "输入: {{input}} tokens, 输出: {{output}} tokens", en: "input: {{input}} tokens, output: {{output}} tokens" }
+    EstimatedCost { zh: "预估费用:", en: "Estimated cost:" }
+    ModelParams { zh: "模型参数:", en: "Model parameters:" }
+    MemeComment { zh: "AI 锐评:", en: "AI verdict:" }
+    IssueDescription { zh: "  描述: ", en: "  Description: " }
+    IssueSuggestion { zh: "  建议: ", en: "  Suggestion:  " }
+    IssuePatch { zh: "  补丁: ", en: "  Patch:       " }
+    IssueModels { zh: "  模型: ", en: "  Models:      " }
+    PatchAvailable { zh: "可自动修复，运行 `ai_git_pre_commit fix` 应用", en: "auto-fixable, run `ai_git_pre_commit fix` to apply" }
+
+    // 提示词
+    SystemPrompt { zh: "系统提示词", en: "System prompt" }
+    UserMessage { zh: "用户消息", en: "User message" }
+    SystemTemplate { zh: "系统提示词模板", en: "System prompt template" }
+    UserTemplate { zh: "用户消息模板", en: "User message template" }
+    UserTemplateNoDiff { zh: "用户消息模板 {{source}} 中没有 {{diff}}，diff 将附加在末尾。", en: "User message template {{source}} has no {{diff}}; the diff will be appended at the end." }
+    TemplateReadFailed { zh: "读取提示词模板失败: {{path}}", en: "Failed to read prompt template: {{path}}" }
+
+    // 安装、卸载与更新
+    InstallStarted { zh: "🚀 开始安装...", en: "🚀 Starting installation..." }
+    InstallDone { zh: "✅ 安装成功完成！", en: "✅ Installation completed successfully!" }
+    DownloadingConfig { zh: "正在下载配置文件...", en: "Downloading configuration file..." }
+    ConfigInstalled { zh: "✅ 配置已安装到 {{path}}", en: "✅ Configuration installed to {{path}}" }
+    ConfigExists { zh: "✅ 配置文件已存在于 {{path}}", en: "✅ Configuration file already exists at {{path}}" }
+    ConfigDownloadFailed { zh: "❌ 下载配置文件失败。", en: "❌ Failed to download the configuration file." }
+    NotGitRepo { zh: "⚠️  当前目录不是 git 仓库。跳过钩子安装。", en: "⚠️  The current directory is not a git repository. Skipping hook installation." }
+    HookInstalled { zh: "✅ {{name}} 钩子已安装到 {{path}}", en: "✅ {{name}} hook installed to {{path}}" }
+    HookRemoved { zh: "✅ {{name}} 钩子已移除。", en: "✅ {{name}} hook removed." }
+    HookNotOurs { zh: "⚠️  发现 {{name}} 钩子，但看起来不是我们的。跳过移除。", en: "⚠️  Found a {{name}} hook that does not look like ours. Skipping removal." }
+    NoPreCommitHook { zh: "ℹ️  未发现 pre-commit 钩子。", en: "ℹ️  No pre-commit hook found." }
+    CheckingUpdates { zh: "🔄 正在检查更新...", en: "🔄 Checking for updates..." }
+    DownloadingFrom { zh: "正在从 {{url}} 下载", en: "Downloading from {{url}}" }
+    UpdateDone { zh: "✅ 更新成功完成！", en: "✅ Update completed successfully!" }
+    UpdateConfigNote { zh: "ℹ️  配置文件 (.env) 未更新。如果丢失，请使用 'install' 下载配置文件。", en: "ℹ️  The configuration file (.env) was not updated. If it is missing, run 'install' to download it." }
+    PathWarning { zh: "⚠️  警告：二进制目录不在您的 PATH 中。", en: "⚠️  Warning: the binary directory is not in your PATH." }
+    PathAdd { zh: "   请将 '{{dir}}' 添加到您的 PATH 环境变量中", en: "   Add '{{dir}}' to your PATH environment variable" }
+    PathReason { zh: "   以便您可以从任何地方运行 'ai_git_pre_commit'。", en: "   so that you can run 'ai_git_pre_commit' from anywhere." }
+    HookRemoveFailed { zh: "移除 {{name}} 钩子失败", en: "Failed to remove the {{name}} hook" }
+    ExePathFailed { zh: "获取当前可执行文件路径失败", en: "Failed to get the current executable path" }
+    ExeDirFailed { zh: "获取可执行文件目录失败", en: "Failed to get the executable directory" }
+    UpdateDownloadFailed { zh: "下载更新失败", en: "Failed to download the update" }
+    UpdateHttpFailed { zh: "下载更新失败: HTTP {{status}}", en: "Failed to download the update: HTTP {{status}}" }
+    UpdateReadFailed { zh: "读取更新数据失败", en: "Failed to read the update data" }
+    ConfigDownloadError { zh: "下载配置失败", en: "Failed to download the configuration" }
+    ConfigWriteFailed { zh: "写入配置文件失败", en: "Failed to write the configuration file" }
+    HooksDirFailed { zh: "创建 hooks 目录失败", en: "Failed to create the hooks directory" }
+    HookWriteFailed { zh: "写入钩子文件失败", en: "Failed to write the hook file" }
+    BinaryRenameFailed { zh: "重命名当前二进制文件失败", en: "Failed to rename the current binary" }
+    BinaryWriteFailed { zh: "写入新二进制文件失败: {{error}}", en: "Failed to write the new binary: {{error}}" }
+
+    // AI 请求与输出解析
+    HttpClientFailed { zh: "创建 HTTP 客户端失败", en: "Failed to create the HTTP client" }
+    ApiRequestFailed { zh: "发送请求给 AI 提供商失败", en: "Failed to send the request to the AI provider" }
+    ApiStatusFailed { zh: "AI API 请求失败 ({{status}}): {{body}}", en: "AI API request failed ({{status}}): {{body}}" }
+    ApiResponseInvalid { zh: "解析 AI 响应 JSON 失败", en: "Failed to parse the AI response JSON" }
+    ApiNoChoices { zh: "AI 响应中没有 choices", en: "The AI response has no choices" }
+    StreamReadFailed { zh: "读取 AI 流式响应失败", en: "Failed to read the AI streaming response" }
+    StreamChunkInvalid { zh: "解析 AI 流式分片失败: {{data}}", en: "Failed to parse an AI streaming chunk: {{data}}" }
+    FallbackModel { zh: "模型 {{model}} 调用失败 ({{error}})，改用备用模型 {{next}}...", en: "Model {{model}} failed ({{error}}), falling back to {{next}}..." }
+    VoterFailed { zh: "模型 {{model}} 调用失败，不参与本次投票: {{error}}", en: "Model {{model}} failed and does not vote this time: {{error}}" }
+    OutputParseFailed { zh: "无法将 AI 输出解析为 JSON。内容: {{content}}", en: "Could not parse the AI output as JSON. Content: {{content}}" }
+    RepairingOutput { zh: "AI 输出无法解析 ({{error}})，正在请求模型修正 ({{attempt}}/{{max}})...", en: "Could not parse the AI output ({{error}}), asking the model to fix it ({{attempt}}/{{max}})..." }
+    JsonSyntax { zh: "JSON 格式错误", en: "Malformed JSON" }
+    NotJsonObject { zh: "输出不是 JSON 对象", en: "The output is not a JSON object" }
+    CheckJsonInvalid { zh: "无法将 JSON 转换为检查结果", en: "Could not convert the JSON into a check result" }
+    SchemaMismatch { zh: "不符合 JSON Schema:", en: "Does not match the JSON Schema:" }
+    SchemaType { zh: "{{path}}: 期望类型 {{expected}}，实际为 {{actual}}", en: "{{path}}: expected {{expected}}, got {{actual}}" }
+    SchemaEnum { zh: "{{path}}: 取值 {{value}} 不在 [{{allowed}}] 中", en: "{{path}}: {{value}} is not one of [{{allowed}}]" }
+    SchemaRequired { zh: "{{path}}: 缺少字段 {{key}}", en: "{{path}}: missing field {{key}}" }
+    TriageFailed { zh: "风险分流失败，直接进行深度审查:", en: "Risk triage failed, running the deep review directly:" }
+    TriageFailedReason { zh: "分流失败", en: "triage failed" }
+    TriageParseFailed { zh: "无法解析分流结果。内容: {{content}}", en: "Could not parse the triage result. Content: {{content}}" }
+    TriageJsonMissing { zh: "输出中没有包含 risk 字段的 JSON", en: "The output has no JSON with a risk field" }
+    TriageJsonInvalid { zh: "无法将 JSON 转换为分流结果", en: "Could not convert the JSON into a triage result" }
+
+    // 本地规则与密钥扫描
+    RuleMergeMarker { zh: "提交中包含未解决的合并冲突标记", en: "The commit contains unresolved merge conflict markers" }
+    RuleMergeMarkerFix { zh: "解决冲突并删除 <<<<<<< / >>>>>>> 标记后再提交", en: "Resolve the conflict and remove the <<<<<<< / >>>>>>> markers before committing" }
+    RuleDebugStatement { zh: "遗留了调试语句: {{code}}", en: "Leftover debug statement: {{code}}" }
+    RuleDebugStatementFix { zh: "删除调试输出，或改用项目的日志框架", en: "Remove the debug output or use the project's logging framework" }
+    RuleTodoTicket { zh: "TODO/FIXME 没有关联任务编号", en: "TODO/FIXME without a ticket reference" }
+    RuleTodoTicketFix { zh: "补充任务编号（如 TODO(PROJ-123) 或 TODO(#42)），方便后续跟踪", en: "Add a ticket reference (e.g. TODO(PROJ-123) or TODO(#42)) so it can be tracked" }
+    RuleLargeFile { zh: "{{file}} 新增了 {{lines}} 行，超过上限 {{max}} 行", en: "{{file}} adds {{lines}} lines, more than the limit of {{max}}" }
+    RuleLargeFileFix { zh: "确认是否误提交了生成文件或数据文件；拆分大文件或加入 .gitignore", en: "Check whether a generated or data file was committed by mistake; split the file or add it to .gitignore" }
+    RuleTrailingWhitespace { zh: "{{count}} 行末尾有多余的空白字符", en: "{{count}} line(s) with trailing whitespace" }
+    RuleTrailingWhitespaceFix { zh: "删除行尾空白，或在编辑器中开启保存时自动清理", en: "Remove the trailing whitespace or enable trimming on save in your editor" }
+    EnvValue { zh: ".env 配置值", en: ".env value" }
+    SecretFound { zh: "检测到疑似 {{name}}，已在发送给 AI 前脱敏", en: "Possible {{name}} detected; redacted before sending to the AI" }
+    SecretFoundFix { zh: "不要把密钥提交到仓库：改用环境变量或密钥管理服务，并立即轮换已泄露的密钥", en: "Do not commit secrets: use environment variables or a secrets manager, and rotate the leaked secret immediately" }
+
+    // 提交信息
+    CommitMsgCheckStarted { zh: "AI 提交信息检查已启动...", en: "AI commit message check started..." }
+    CommitMsgMergeSkipped { zh: "检测到合并操作。跳过自动生成的合并提交信息。", en: "Merge in progress. Skipping the generated merge commit message." }
+    CommitMsgApproved { zh: "✅ 提交信息已通过！", en: "✅ Commit message approved!" }
+    CommitMsgRejected { zh: "❌ 提交信息不符合规范。", en: "❌ The commit message does not follow the conventions." }
+    CommitMsgReadFailed { zh: "读取提交信息文件失败: {{path}}", en: "Failed to read the commit message file: {{path}}" }
+    CommitMsgWriteFailed { zh: "写入提交信息文件失败: {{path}}", en: "Failed to write the commit message file: {{path}}" }
+    SubjectTooLong { zh: "标题行长度为 {{len}} 个字符，超过限制 {{max}}", en: "The subject line is {{len}} characters long, over the limit of {{max}}" }
+    TicketMissing { zh: "提交信息中没有匹配 {{pattern}} 的需求/缺陷单号", en: "The commit message has no ticket ID matching {{pattern}}" }
+    TicketMissingFix { zh: "在标题或正文中补充单号", en: "Add the ticket ID to the subject or the body" }
+    SubjectTooLongFix { zh: "缩短标题，把细节移到正文中（标题与正文之间空一行）", en: "Shorten the subject and move details into the body (with a blank line in between)" }
+    NoMonitoredStaged { zh: "在监控的文件中未发现暂存的更改。", en: "No staged changes in monitored files." }
+    GenerateMessageFailed { zh: "生成提交信息失败:", en: "Failed to generate the commit message:" }
+    // Git
+    #[cfg(feature = "libgit2")]
+    BackendFallback { zh: "{{backend}} 后端失败，回退到 git 命令行: {{error}}", en: "The {{backend}} backend failed, falling back to the git command line: {{error}}" }
+    #[cfg(not(feature = "libgit2"))]
+    Libgit2Disabled { zh: "当前构建未启用 libgit2 feature，使用 git 命令行。", en: "This build does not include the libgit2 feature; using the git command line." }
+    StagedFilesFailed { zh: "读取暂存区文件列表失败 (backend: {{backend}})", en: "Failed to list staged files (backend: {{backend}})" }
+    StagedDiffFailed { zh: "生成暂存区 diff 失败 (backend: {{backend}})", en: "Failed to generate the staged diff (backend: {{backend}})" }
+    RangeFilesFailed { zh: "读取 {{range}} 的变更文件失败", en: "Failed to list changed files in {{range}}" }
+    RangeDiffFailed { zh: "生成 {{range}} 的 diff 失败", en: "Failed to generate the diff for {{range}}" }
+    GitApplyStdinFailed { zh: "无法写入 git apply 的标准输入", en: "Failed to write to the standard input of git apply" }
+    #[cfg(feature = "libgit2")]
+    BareRepository { zh: "裸仓库没有工作区", en: "A bare repository has no working directory" }
+
+    // CI 与 PR 评论
+    UnknownCi { zh: "未知 CI", en: "unknown CI" }
+    CiBaseUnknown { zh: "无法从 {{platform}} 的环境变量确定 PR 的目标分支，请使用 --base 指定", en: "Could not determine the PR target branch from the {{platform}} environment; specify it with --base" }
+    CodeQualityWriteFailed { zh: "写入 Code Quality 报告失败: {{path}}", en: "Failed to write the Code Quality report: {{path}}" }
+    StepSummaryFailed { zh: "打开 GITHUB_STEP_SUMMARY 失败: {{path}}", en: "Failed to open GITHUB_STEP_SUMMARY: {{path}}" }
+    SummaryTitle { zh: "AI 代码审查", en: "AI code review" }
+    SummaryVerdict { zh: "**结论**: {{verdict}}（失败阈值: `{{threshold}}`）", en: "**Verdict**: {{verdict}} (fail threshold: `{{threshold}}`)" }
+    SummaryPassed { zh: "✅ 通过", en: "✅ Passed" }
+    SummaryFailed { zh: "❌ 未通过", en: "❌ Failed" }
+    SummaryNoIssues { zh: "没有发现问题。", en: "No issues found." }
+    SummaryTableHeader { zh: "| 严重级别 | 视角 | 位置 | 问题 | 建议 |", en: "| Severity | Perspective | Location | Issue | Suggestion |" }
+    ForgeUnknown { zh: "无法判断代码托管平台，请设置 AI_CHECK_FORGE", en: "Could not determine the code hosting platform; set AI_CHECK_FORGE" }
+    ForgeTokenMissing { zh: "未配置平台访问令牌，请设置 AI_CHECK_FORGE_TOKEN 或 {{vars}}", en: "No platform access token configured; set AI_CHECK_FORGE_TOKEN or {{vars}}" }
+    ForgePrUnknown { zh: "无法确定 PR 编号，请使用 --pr 指定", en: "Could not determine the PR number; specify it with --pr" }
+    ForgeMrUnknown { zh: "无法确定 MR 编号，请使用 --pr 指定", en: "Could not determine the MR number; specify it with --pr" }
+    GiteaApiUnknown { zh: "无法确定 Gitea API 地址，请设置 AI_CHECK_FORGE_API", en: "Could not determine the Gitea API URL; set AI_CHECK_FORGE_API" }
+    ForgeHeadMissing { zh: "PR 信息中缺少 head.sha", en: "The PR has no head.sha" }
+    ForgeDiffRefsMissing { zh: "MR 信息中缺少 diff_refs", en: "The MR has no diff_refs" }
+    ForgeRequestFailed { zh: "请求 {{url}} 失败", en: "Request to {{url}} failed" }
+    ForgeStatus { zh: "{{request}} 返回 {{status}}: {{body}}", en: "{{request}} returned {{status}}: {{body}}" }
+    ForgeResponseInvalid { zh: "解析 {{url}} 的响应失败", en: "Failed to parse the response from {{url}}" }
+    ForgeNotList { zh: "{{path}} 返回的不是列表: {{body}}", en: "{{path}} did not return a list: {{body}}" }
+    ForgeCommentsFailed { zh: "读取已有评论失败", en: "Failed to read the existing comments" }
+    ForgeSummaryFailed { zh: "发表汇总评论失败", en: "Failed to post the summary comment" }
+    ForgeInlineListFailed { zh: "读取已有的行内评论失败，未发表行内评论", en: "Failed to read the existing inline comments; no inline comments were posted" }
+    ForgeInlineFailed { zh: "发表 {{path}}:{{line}} 的行内评论失败", en: "Failed to post the inline comment on {{path}}:{{line}}" }
+
+
+    // 修复与交互
+    PatchesRejected { zh: "{{count}} 个修复补丁无法应用到暂存区或与问题位置不符，已忽略。", en: "Ignored {{count}} fix patch(es) that do not apply to the index or do not match the issue location." }
+    PatchesSaveFailed { zh: "保存修复补丁失败:", en: "Failed to save the fix patches:" }
+    NoFixes { zh: "上一次检查没有可自动修复的问题。", en: "The last check found no auto-fixable issues." }
+    PatchStale { zh: "补丁已无法应用，跳过:", en: "The patch no longer applies, skipping:" }
+    ApplyPatchPrompt { zh: "应用该补丁并重新暂存?", en: "Apply this patch and stage it again?" }
+    PatchApplied { zh: "已应用。", en: "Applied." }
+    PatchAppliedStaged { zh: "已应用并重新暂存。", en: "Applied and staged again." }
+    PatchApplyFailed { zh: "应用失败:", en: "Failed to apply:" }
+    PatchesApplied { zh: "已应用 {{count}} 个补丁。", en: "Applied {{count}} patch(es)." }
+    InteractiveTitle { zh: "交互式处理被拒绝的问题:", en: "Handle the blocking issues interactively:" }
+    InteractiveChoices { zh: "查看上下文 (d) / 标记为误报 (f){{patch}} / 接受并继续 (a) / 中止提交 (q)", en: "show context (d) / mark as false positive (f){{patch}} / accept and continue (a) / abort the commit (q)" }
+    ChoicePatch { zh: " / 应用补丁 (p)", en: " / apply the patch (p)" }
+    BaselineWritten { zh: "已将 {{count}} 个问题写入 {{path}}，记得一并提交该文件。", en: "Wrote {{count}} issue(s) to {{path}}; remember to commit that file too." }
+    LocationNotInDiff { zh: "diff 中找不到该位置。", en: "The location is not in the diff." }
+    LinesOmitted { zh: "... 省略 {{count}} 行", en: "... {{count}} more line(s)" }
+    DryRunLabel { zh: "预演:", en: "Dry run:" }
+    DryRunWritten { zh: "已将 {{count}} 个请求写入 {{path}}，未调用 API。", en: "Wrote {{count}} request(s) to {{path}}; the API was not called." }
+    DryRunNoApi { zh: "未调用 API。", en: "The API was not called." }
+
+    // 用量记录
+    UsageWriteFailed { zh: "写入用量记录失败:", en: "Failed to write the usage record:" }
+    UsageDirFailed { zh: "创建用量记录目录失败", en: "Failed to create the usage ledger directory" }
+    UsageOpenFailed { zh: "打开用量记录文件失败: {{path}}", en: "Failed to open the usage ledger: {{path}}" }
+    UsageReadFailed { zh: "读取用量记录失败", en: "Failed to read the usage ledger" }
+    UsageEmpty { zh: "ℹ️  暂无用量记录。", en: "ℹ️  No usage recorded yet." }
+    UsageLedger { zh: "用量记录:", en: "Usage ledger:" }
+    UsageByDate { zh: "按日期", en: "By date" }
+    UsageByAuthor { zh: "按提交者", en: "By author" }
+    UsageByModel { zh: "按模型", en: "By model" }
+    UsageRuns { zh: "次数", en: "Runs" }
+    UsageInput { zh: "输入 tokens", en: "Input tokens" }
+    UsageOutput { zh: "输出 tokens", en: "Output tokens" }
+    UsageCost { zh: "费用 ($)", en: "Cost ($)" }
+    UsageUnpriced { zh: " (+{{count}} 次未计价)", en: " (+{{count}} unpriced)" }
+}
@@ -0,0 +1,148 @@
+use crate::ai::{AiCheckResult, Issue};
+use crate::baseline::Baseline;
+use crate::config::Config;
+use crate::i18n::Msg;
+use crate::{diff, fix};
+use anyhow::Result;
+use colored::*;
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+
+#[cfg(windows)]
+const TTY: &str = "CONIN$";
+#[cfg(not(windows))]
+const TTY: &str = "/dev/tty";
+
+/// 查看上下文时最多显示的 diff 行数
+const MAX_CONTEXT_LINES: usize = 40;
+
+/// 打开控制终端。Git 钩子的标准输入不是终端，必须直接读 `/dev/tty`；
+/// 在 CI 或没有控制终端的环境中返回 None。
+pub fn open_tty(config: &Config) -> Option<BufReader<File>> {
+    if !config.interactive || env::var_os("CI").is_some() {
+        return None;
+    }
+    File::open(TTY).ok().map(BufReader::new)
+}
+
+/// 提交被拒绝后逐条处理阻塞问题，返回是否继续提交。
+///
+/// 阻塞问题为 high 级别的问题（没有时为全部问题）；每条都被接受或标记为误报才继续。
+pub fn resolve(
+    tty: &mut impl BufRead,
+    result: &AiCheckResult,
+    diff_text: &str,
+    baseline: &mut Baseline,
+) -> Result<bool> {
+    let high: Vec<&Issue> = result
+        .list
+        .iter()
+        .filter(|i| i.severity.eq_ignore_ascii_case("high"))
+        .collect();
+    let blocking = if high.is_empty() {
+        result.list.iter().collect()
+    } else {
+        high
+    };
+
+    if blocking.is_empty() {
+        return Ok(false);
+    }
+
+    println!("{}", Msg::InteractiveTitle.text().bold().underline());
+
+    let mut suppressed = 0;
+    for (i, issue) in blocking.iter().enumerate() {
+        println!(
+            "\n[{}/{}] [{}] [{}] {}",
+            i + 1,
+            blocking.len(),
+            issue.severity.red().bold(),
+            issue.perspective.cyan(),
+            issue.location.white().italic()
+        );
+        println!("{}{}", Msg::IssueDescription, issue.description);
+
+        let fix_option = if issue.patch.is_some() {
+            Msg::ChoicePatch.text()
+        } else {
+            ""
+        };
+
+        loop {
+            print!(
+                "  {} {}: ",
+                "?".yellow().bold(),
+                Msg::InteractiveChoices.fill(&[("patch", fix_option.to_string())])
+            );
+            io::stdout().flush()?;
+
+            let mut answer = String::new();
+            if tty.read_line(&mut answer)? == 0 {
+                // 终端被关闭，按中止处理
+                return Ok(false);
+            }
+
+            match answer.trim().to_lowercase().as_str() {
+                "d" => print_context(diff_text, issue),
+                "f" => {
+                    baseline.add(issue, diff_text);
+                    suppressed += 1;
+                    break;
+                }
+                "p" if issue.patch.is_some() => match fix::apply_interactively(tty, issue) {
+                    Ok(true) => break,
+                    Ok(false) => continue,
+                    Err(e) => println!("  {} {}", Msg::PatchApplyFailed.text().red(), e),
+                },
+                "a" => break,
+                "q" => return Ok(false),
+                _ => continue,
+            }
+        }
+    }
+
+    if suppressed > 0 {
+        baseline.save()?;
+        println!(
+            "\n{} {}",
+            Msg::Baseline.text().cyan(),
+            Msg::BaselineWritten.fill(&[
+                ("count", suppressed.to_string()),
+                ("path", format!("{:?}", baseline.path())),
+            ])
+        );
+    }
+
+    Ok(true)
+}
+
+fn print_context(diff_text: &str, issue: &Issue) {
+    let hunk = diff::parse_location(&issue.location)
+        .and_then(|(file, line)| diff::hunk_at(diff_text, file, line));
+
+    let Some(hunk) = hunk else {
+        println!("  {}", Msg::LocationNotInDiff.text().dimmed());
+        return;
+    };
+
+    let total = hunk.len();
+    for line in hunk.into_iter().take(MAX_CONTEXT_LINES) {
+        let colored = match line.chars().next() {
+            Some('+') => line.green(),
+            Some('-') => line.red(),
+            Some('@') => line.cyan(),
+            _ => line.normal(),
+        };
+        println!("    {}", colored);
+    }
+    if total > MAX_CONTEXT_LINES {
+        println!(
+            "    {}",
+            Msg::LinesOmitted
+                .fill(&[("count", (total - MAX_CONTEXT_LINES).to_string())])
+                .dimmed()
+        );
+    }
+}
@@ -1,14 +1,43 @@
 mod ai;
+mod baseline;
+mod chunk;
+mod ci;
 mod cli;
 mod commands;
+mod commit_msg;
 mod config;
+mod consensus;
+mod context;
+mod diff;
+mod digest;
+mod dry_run;
+mod filter;
+mod fix;
+mod forge;
 mod git;
+mod i18n;
+mod interactive;
+mod parse;
+mod progress;
 mod prompts;
+mod report;
+mod review;
+mod rules;
+mod schema;
+mod secrets;
+mod suggest;
+mod template;
+mod tokens;
+mod triage;
+mod usage;
 
 use anyhow::Result;
 use clap::Parser;
-use cli::{Cli, Commands};
+use cli::{CheckArgs, CiArgs, Cli, Commands, PromptAction, ReviewArgs};
 use colored::*;
+use config::ReviewPolicy;
+use i18n::Msg;
+use std::path::Path;
 use std::process::exit;
 
 #[tokio::main]
@@ -19,8 +48,11 @@ async fn main() -> Result<()> {
     // 处理子命令
     if let Some(command) = cli.command {
         match command {
-            Commands::Install => {
-                commands::install().await?;
+            Commands::Install {
+                commit_msg,
+                prepare_commit_msg,
+            } => {
+                commands::install(commit_msg, prepare_commit_msg).await?;
                 return Ok(());
             }
             Commands::Uninstall => {
@@ -31,116 +63,493 @@ async fn main() -> Result<()> {
                 commands::update().await?;
                 return Ok(());
             }
-            _ => {}
+            Commands::CommitMsg { file } => {
+                return commit_msg::run(&load_config(), &file).await;
+            }
+            Commands::PrepareCommitMsg { file, source, .. } => {
+                return suggest::prepare_commit_msg(&load_config(), &file, source.as_deref())
+                    .await;
+            }
+            Commands::SuggestMessage => {
+                return suggest::run(&load_config()).await;
+            }
+            Commands::Fix => {
+                return fix::run(&load_config());
+            }
+            Commands::Usage { days } => {
+                return usage::summarize(&load_config(), days);
+            }
+            Commands::Check(args) => {
+                return run_check(args).await;
+            }
+            Commands::Review(args) => {
+                return run_review(args).await;
+            }
+            Commands::Ci(args) => {
+                return run_ci(args).await;
+            }
+            Commands::Prompt {
+                action: PromptAction::Print { template },
+            } => {
+                return prompts::print(&load_config(), template);
+            }
         }
     }
 
     // 默认运行检查
-    run_check().await
+    run_check(CheckArgs::default()).await
 }
 
-async fn run_check() -> Result<()> {
-    // 加载配置文件
-    let config = match config::Config::load() {
+/// 加载配置文件，失败时打印提示并以状态码 1 退出。
+fn load_config() -> config::Config {
+    match config::Config::load() {
         Ok(cfg) => cfg,
         Err(e) => {
-            eprintln!("{} {}", "配置错误:".red().bold(), e);
-            eprintln!("请检查您的 .env 文件或环境变量。");
+            eprintln!("{} {}", Msg::ConfigError.text().red().bold(), e);
+            eprintln!("{}", Msg::ConfigHint);
             exit(1);
         }
-    };
+    }
+}
 
-    println!("{}", "AI Git Pre-Commit 检查已启动...".blue().bold());
+async fn run_check(args: CheckArgs) -> Result<()> {
+    // 加载配置文件
+    let config = load_config();
+
+    println!("{}", Msg::CheckStarted.text().blue().bold());
 
     // 2. 获取暂存区的代码变更 diff
-    // 如果当前是合并提交，则跳过检查
-    if let Ok(true) = git::is_merge_in_progress() {
-        println!("{}", "检测到合并操作。跳过 AI 检查以避免分析大量合并代码。".yellow());
+    // merge/rebase/cherry-pick/revert 过程中按配置决定跳过、全量审查或只审查冲突解决
+    let state = git::repo_state(&config).unwrap_or(git::RepoState::Clean);
+    let policy = match state {
+        git::RepoState::Clean => ReviewPolicy::Review,
+        git::RepoState::Merge => config.on_merge,
+        git::RepoState::Rebase => config.on_rebase,
+        git::RepoState::CherryPick => config.on_cherry_pick,
+        git::RepoState::Revert => config.on_revert,
+    };
+
+    let diff = match policy {
+        ReviewPolicy::Skip if state == git::RepoState::Merge => {
+            println!("{}", Msg::MergeSkipped.text().yellow());
+            exit(0);
+        }
+        ReviewPolicy::Skip => {
+            println!(
+                "{}",
+                Msg::OperationSkipped
+                    .fill(&[("operation", state.label().to_string())])
+                    .yellow()
+            );
+            exit(0);
+        }
+        ReviewPolicy::Review => git::get_staged_diff_unfiltered(&config),
+        ReviewPolicy::Conflicts => {
+            println!(
+                "{}",
+                Msg::ConflictsOnly.fill(&[("operation", state.label().to_string())])
+            );
+            git::get_resolution_diff(&config, state)
+        }
+    };
+
+    let diff = match diff {
+        Ok(d) => d,
+        Err(e) => {
+            eprintln!("{} {}", Msg::GitError.text().red().bold(), e);
+            exit(1);
+        }
+    };
+
+    if diff.trim().is_empty() {
+        println!("{}", Msg::NoStagedChanges.text().yellow());
         exit(0);
     }
 
-    let diff = match git::get_staged_diff(&config) {
+    review_diff(&config, diff, &args, Source::Staged).await
+}
+
+/// 被审查的 diff 来自哪里，决定结果的后续处理方式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Source {
+    /// 暂存区：补丁可以校验并通过 `fix` 应用
+    Staged,
+    /// `review` 子命令为任意文件生成的伪 diff
+    Files,
+}
+
+/// `review` 子命令：把指定的文件或目录整体当作新增内容审查，不要求在 git 仓库中。
+async fn run_review(args: ReviewArgs) -> Result<()> {
+    let config = load_config();
+
+    println!("{}", Msg::ReviewStarted.text().blue().bold());
+
+    let diff = match review::pseudo_diff(&config, &args.paths) {
         Ok(d) => d,
         Err(e) => {
-            eprintln!("{} {}", "Git 错误:".red().bold(), e);
+            eprintln!("{} {:#}", Msg::ReadFilesFailed.text().red().bold(), e);
             exit(1);
         }
     };
 
     if diff.trim().is_empty() {
-        println!(
-            "{}",
-            "在监控的文件中未发现暂存的更改。跳过检查。".yellow()
-        );
+        println!("{}", Msg::NoFilesToReview.text().yellow());
         exit(0);
     }
 
+    review_diff(&config, diff, &args.check, Source::Files).await
+}
+
+/// `ci` 子命令：审查 PR/MR 引入的变更，按平台输出注解或报告，并按严重级别阈值设置退出码。
+async fn run_ci(args: CiArgs) -> Result<()> {
+    let mut config = load_config();
+    if let Some(fail_on) = args.fail_on {
+        config.ci_fail_on = fail_on;
+    }
+
+    let platform = ci::Platform::detect();
     println!(
-        "{} 正在分析 {} 个字符的代码变更...",
-        "处理中:".cyan(),
-        diff.len()
+        "{} ({})",
+        Msg::ReviewStarted.text().blue().bold(),
+        platform.label()
     );
 
-    // 3. 生成提示词
-    let system_prompt = prompts::generate_system_prompt(&config);
+    let (base, head) = match ci::revisions(platform, args.base, args.head) {
+        Ok(revs) => revs,
+        Err(e) => {
+            eprintln!("{} {}", Msg::Error.text().red().bold(), e);
+            exit(1);
+        }
+    };
+    println!("{} {}...{}", Msg::ReviewRange.text().cyan(), base, head);
+    if !config.ci_trust_head {
+        // 提示词模板和项目上下文以目标分支为准，PR 中对它们的修改合并后才生效
+        config.trusted_rev = Some(base.clone());
+    }
 
-    // 4. 调用 AI
-    let result = match ai::call_ai_check(&config, system_prompt, diff).await {
-        Ok(res) => res,
+    let diff = match git::get_range_diff(&base, &head) {
+        Ok(d) => d,
         Err(e) => {
-            eprintln!("{} {}", "AI 检查失败:".red().bold(), e);
+            eprintln!("{} {:#}", Msg::DiffFailed.text().red().bold(), e);
             exit(1);
         }
     };
 
-    // 5. 显示结果
-    println!("\n{}", "分析结果:".bold().underline());
-    
-    // 显示 Token 使用情况
-    if let Some(usage) = &result.usage {
+    if diff.trim().is_empty() {
+        println!("{}", Msg::NoChanges.text().yellow());
+        exit(0);
+    }
+
+    let Analysis {
+        mut result,
+        diff,
+        suppressed,
+        ..
+    } = analyze(&config, diff, &args.check, Some("ci")).await?;
+
+    // CI 中不会应用补丁
+    drop_patches(&mut result);
+    for entry in &result.usage {
+        usage::record(&config, "ci", entry);
+    }
+
+    report::print_result(&config, &result);
+    if suppressed > 0 {
         println!(
-            "{} 输入: {} tokens, 输出: {} tokens, 总计: {} tokens",
-            "Token 使用:".purple(),
-            usage.prompt_tokens,
-            usage.completion_tokens,
-            usage.total_tokens
+            "{} {}",
+            Msg::Baseline.text().cyan(),
+            Msg::BaselineSuppressed.fill(&[("count", suppressed.to_string())])
         );
     }
 
-    // 显示 AI 锐评
-    if let Some(comment) = &result.meme_comment {
-        println!("\n{}", "AI 锐评:".magenta().bold());
-        println!("{}", comment.italic());
+    let failed = ci::fails(&config, &result);
+
+    if platform == ci::Platform::GitHub {
+        ci::print_annotations(&result);
     }
 
-    for issue in &result.list {
-        let severity_color = match issue.severity.to_lowercase().as_str() {
-            "high" => "red",
-            "medium" => "yellow",
-            "low" => "green",
-            _ => "white",
-        };
+    let code_quality = args.code_quality.or_else(|| {
+        (platform == ci::Platform::GitLab).then(|| ci::CODE_QUALITY_REPORT.into())
+    });
+    if let Some(path) = code_quality {
+        match ci::write_code_quality(&result, &path) {
+            Ok(()) => println!("{} {:?}", Msg::CodeQualityWritten.text().cyan(), path),
+            Err(e) => eprintln!("{} {:#}", Msg::Warning.text().yellow(), e),
+        }
+    }
+
+    if let Err(e) = ci::write_step_summary(&config, &result, failed) {
+        eprintln!("{} {:#}", Msg::Warning.text().yellow(), e);
+    }
+
+    // 评论发表失败不影响流水线结论
+    if args.comment {
+        match forge::publish(&config, platform, &result, &diff, failed, args.pr).await {
+            Ok(p) => {
+                let msg = if p.updated {
+                    Msg::PrCommentUpdated
+                } else {
+                    Msg::PrCommentPosted
+                };
+                println!(
+                    "{} {}",
+                    Msg::PrComment.text().cyan(),
+                    msg.fill(&[
+                        ("forge", p.forge.to_string()),
+                        ("inline", p.inline.to_string()),
+                        ("duplicates", p.duplicates.to_string()),
+                    ])
+                );
+                for e in &p.failures {
+                    eprintln!("{} {:#}", Msg::Warning.text().yellow(), e);
+                }
+            }
+            Err(e) => eprintln!("{} {:#}", Msg::PrCommentFailed.text().yellow(), e),
+        }
+    }
 
+    if failed {
         println!(
-            "\n[{}] [{}] {}",
-            issue.severity.color(severity_color).bold(),
-            issue.perspective.cyan(),
-            issue.location.white().italic()
+            "{}",
+            Msg::CiThresholdFailed
+                .fill(&[("threshold", config.ci_fail_on.clone())])
+                .red()
+                .bold()
         );
-        println!("  Description: {}", issue.description);
-        println!("  Suggestion:  {}", issue.suggestion);
+        exit(1);
     }
+    println!("{}", Msg::Approved.text().green().bold());
+    exit(0);
+}
+
+/// 一次审查的结果，以及报告和交互处理需要的上下文。
+struct Analysis {
+    result: ai::AiCheckResult,
+    /// 脱敏后的完整 diff（含审查范围外的文件），问题位置与基线都以它为准
+    diff: String,
+    baseline: baseline::Baseline,
+    /// 被基线忽略的问题数
+    suppressed: usize,
+}
+
+/// 审查 diff 并按来源处理结果、显示报告后退出。
+async fn review_diff(
+    config: &config::Config,
+    diff: String,
+    args: &CheckArgs,
+    source: Source,
+) -> Result<()> {
+    // 不在 git 仓库中审查文件时没有地方存放用量记录
+    let ledger = match source {
+        Source::Staged => Some("check"),
+        Source::Files => git::backend(config).git_dir().is_ok().then_some("review"),
+    };
 
-    println!();
+    let Analysis {
+        mut result,
+        diff,
+        mut baseline,
+        suppressed,
+    } = analyze(config, diff, args, ledger).await?;
 
-    if result.result.to_uppercase().contains("YES") {
-        println!("{}", "✅ 代码已通过！".green().bold());
-        exit(0);
+    // 只保留能干净应用到暂存区的补丁，并记下来供 `fix` 子命令使用；
+    // 伪 diff 不对应暂存区，补丁无从校验
+    match source {
+        Source::Staged => {
+            fix::validate_patches(&mut result);
+            fix::save(config, &result);
+        }
+        Source::Files => drop_patches(&mut result),
+    }
+
+    // 5. 显示结果并记录用量
+    if let Some(command) = ledger {
+        for entry in &result.usage {
+            usage::record(config, command, entry);
+        }
+    }
+    finish(config, &result, &diff, &mut baseline, suppressed, source);
+}
+
+/// 本地规则、密钥脱敏、分块和 AI 审查，`check`、`review` 与 `ci` 共用。
+///
+/// `--dry-run` 时输出请求后直接退出；本地检查已能确定拒绝时不调用 AI。
+/// AI 审查失败时把已经消耗的 Token 记入 `ledger` 指定命令的用量记录后退出。
+async fn analyze(
+    config: &config::Config,
+    diff: String,
+    args: &CheckArgs,
+    ledger: Option<&str>,
+) -> Result<Analysis> {
+    // 密钥在本地检测并脱敏，原文不会发送给模型。扫描覆盖全部变更文件（包括 `.env` 等
+    // 不在审查范围内的文件），之后才按扩展名和排除规则筛出交给 AI 审查的部分
+    let (diff, mut local_issues) = if config.redact_secrets {
+        let scan = secrets::scan(&diff);
+        if !scan.issues.is_empty() {
+            println!(
+                "{} {}",
+                Msg::Warning.text().yellow().bold(),
+                Msg::SecretsRedacted.fill(&[("count", scan.issues.len().to_string())])
+            );
+        }
+        (scan.redacted, scan.issues)
     } else {
+        (diff, Vec::new())
+    };
+    let reviewed = diff::retain_files(&diff, |file| filter::is_monitored(config, Path::new(file)));
+
+    // 本地规则不依赖网络，先于 AI 运行
+    local_issues.extend(rules::check(config, &reviewed));
+    if reviewed.trim().is_empty() && local_issues.is_empty() {
+        println!("{}", Msg::NoMonitoredChanges.text().yellow());
+        exit(0);
+    }
+
+    // 3. 生成提示词（包含与变更相关的项目上下文），并按模型的 Token 预算切分 diff
+    let contexts = context::load(config, &reviewed);
+    if !contexts.is_empty() {
+        let paths: Vec<&str> = contexts.iter().map(|c| c.path.as_str()).collect();
+        println!("{} {}", Msg::ProjectContext.text().cyan(), paths.join(", "));
+    }
+    let prompt = match prompts::review_prompt(config, &contexts, &reviewed) {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("{} {:#}", Msg::TemplateError.text().red().bold(), e);
+            exit(1);
+        }
+    };
+    let budget = tokens::chunk_budget(config, &prompt.system, &prompt.user_overhead());
+    let chunks = chunk::split_diff(&config.model, &reviewed, budget);
+
+    println!(
+        "{} {}",
+        Msg::Processing.text().cyan(),
+        Msg::AnalyzingChanges.fill(&[
+            ("tokens", tokens::count(&config.model, &reviewed).to_string()),
+            ("chunks", chunks.len().to_string()),
+            ("budget", budget.to_string()),
+        ])
+    );
+
+    if args.dry_run {
+        dry_run::emit(config, &prompt, &chunks, budget, args.output.as_deref())?;
+        exit(0);
+    }
+
+    // 误报基线：标记过的问题不再报告，也不再阻止提交
+    let baseline = match baseline::Baseline::load(config) {
+        Ok(b) => b,
+        Err(e) => {
+            eprintln!("{} {:#}", Msg::BaselineError.text().red().bold(), e);
+            exit(1);
+        }
+    };
+
+    let mut local = ai::AiCheckResult::local();
+    local.prepend_local_issues(local_issues);
+    let mut suppressed = baseline.apply(&mut local, &diff);
+
+    // 确定无误的本地问题（冲突标记、固定格式的密钥）足以拒绝时不再调用 AI，
+    // 节省 Token，也不受网络影响；其余本地问题随 AI 的结果一起报告
+    if local.list.iter().any(rules::is_blocking) {
+        println!("{}", Msg::LocalBlocked.text().yellow());
+        return Ok(Analysis {
+            result: local,
+            diff,
+            baseline,
+            suppressed,
+        });
+    }
+
+    // 4. 逐块调用 AI 并合并结果
+    let mut merged: Option<ai::AiCheckResult> = None;
+    let total = chunks.len();
+    for (i, chunk) in chunks.into_iter().enumerate() {
+        progress::set_chunks(i, total);
+        if i > 0 {
+            println!(
+                "{} {}",
+                Msg::Processing.text().cyan(),
+                Msg::ReviewingChunk.fill(&[("n", (i + 1).to_string()), ("total", total.to_string())])
+            );
+        }
+
+        let result = match triage::review(config, &prompt, chunk).await {
+            Ok(res) => res,
+            Err(e) => {
+                if let Some(command) = ledger {
+                    let done = merged.iter().flat_map(|r| r.usage.iter());
+                    for entry in done.chain(ai::spent_usage(&e)) {
+                        usage::record(config, command, entry);
+                    }
+                }
+                eprintln!("{} {}", Msg::AiCheckFailed.text().red().bold(), e);
+                exit(1);
+            }
+        };
+
+        merged = Some(match merged {
+            Some(prev) => prev.merge(result),
+            None => result,
+        });
+    }
+
+    // 只有审查范围外的文件发现了问题时没有可发送的块
+    let mut result = merged.unwrap_or_else(ai::AiCheckResult::local);
+    result.prepend_local_issues(local.list);
+    suppressed += baseline.apply(&mut result, &diff);
+
+    Ok(Analysis {
+        result,
+        diff,
+        baseline,
+        suppressed,
+    })
+}
+
+/// 结果不对应暂存区时，模型给出的补丁无从校验，也无法应用。
+fn drop_patches(result: &mut ai::AiCheckResult) {
+    result.list.iter_mut().for_each(|issue| issue.patch = None);
+}
+
+/// 显示结果并以相应的状态码退出；审查暂存区被拒绝且连接终端时进入交互式处理。
+fn finish(
+    config: &config::Config,
+    result: &ai::AiCheckResult,
+    diff: &str,
+    baseline: &mut baseline::Baseline,
+    suppressed: usize,
+    source: Source,
+) -> ! {
+    report::print_result(config, result);
+    if suppressed > 0 {
         println!(
-            "{}",
-            "❌ 代码被拒绝，发现严重问题。".red().bold()
+            "{} {}",
+            Msg::Baseline.text().cyan(),
+            Msg::BaselineSuppressed.fill(&[("count", suppressed.to_string())])
         );
+    }
+
+    if result.is_approved() {
+        println!("{}", Msg::Approved.text().green().bold());
+        exit(0);
+    }
+
+    println!("{}", Msg::Rejected.text().red().bold());
+
+    if source != Source::Staged {
         exit(1);
     }
+
+    if let Some(mut tty) = interactive::open_tty(config) {
+        match interactive::resolve(&mut tty, result, diff, baseline) {
+            Ok(true) => {
+                println!("{}", Msg::AllResolved.text().green().bold());
+                exit(0);
+            }
+            Ok(false) => println!("{}", Msg::CommitAborted.text().yellow()),
+            Err(e) => eprintln!("{} {:#}", Msg::InteractiveFailed.text().red().bold(), e),
+        }
+    }
+    exit(1);
 }
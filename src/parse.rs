@@ -0,0 +1,306 @@
+use crate::ai::AiCheckResult;
+use crate::i18n::Msg;
+use crate::schema;
+use crate::triage::Triage;
+use anyhow::{Context, Result};
+use std::fmt;
+
+/// 输出是合法的 JSON，但不符合 Schema（取值不在枚举中、缺少字段等）。
+///
+/// 这类问题本地无法修补，应交给模型修正，而不是退回宽松解析。
+#[derive(Debug)]
+pub struct SchemaError(pub Vec<String>);
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", Msg::SchemaMismatch, self.0.join("; "))
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+/// 将模型输出解析为 `AiCheckResult`。
+///
+/// `strict` 为 true 时（服务端已启用结构化输出）内容本身必须是符合 Schema 的 JSON；
+/// 否则从可能夹杂说明文字的输出中容错提取。
+pub fn parse_check_result(content: &str, strict: bool) -> Result<AiCheckResult> {
+    let content = content.trim();
+
+    if !strict {
+        // 内容应该是 JSON，但模型可能在前后添加说明文字或 ```json 代码块。
+        let json_str = extract_json(content, "result").unwrap_or_else(|| find_json_bounds(content));
+        let mut value: serde_json::Value =
+            serde_json::from_str(json_str).context(Msg::JsonSyntax.text())?;
+        fill_optional(&mut value);
+        return from_validated(value, &schema::lenient_check_result_schema());
+    }
+
+    // 结构化输出模式下内容本身就应该是 JSON，按 Schema 严格校验
+    let value: serde_json::Value =
+        serde_json::from_str(strip_code_fence(content)).context(Msg::JsonSyntax.text())?;
+
+    from_validated(value, &schema::check_result_schema())
+}
+
+/// 按 Schema 校验后转换为检查结果，不符合时返回 `SchemaError`。
+fn from_validated(
+    mut value: serde_json::Value,
+    schema: &serde_json::Value,
+) -> Result<AiCheckResult> {
+    normalize(&mut value);
+    let errors = schema::validate(&value, schema);
+    if !errors.is_empty() {
+        return Err(SchemaError(errors).into());
+    }
+
+    serde_json::from_value(value).context(Msg::CheckJsonInvalid.text())
+}
+
+/// 统一模型常见的写法差异，避免仅因大小写或附加说明被当作不符合 Schema：
+/// `result` 为 `"NO (rejected)"`、`"yes"` 等时取开头的 YES/NO，`severity` 转为小写。
+fn normalize(value: &mut serde_json::Value) {
+    if let Some(result) = value.get_mut("result") {
+        let verdict = result.as_str().and_then(|text| {
+            let word: String = text
+                .trim()
+                .chars()
+                .take_while(char::is_ascii_alphabetic)
+                .collect();
+            let word = word.to_ascii_uppercase();
+            matches!(word.as_str(), "YES" | "NO").then_some(word)
+        });
+        if let Some(verdict) = verdict {
+            *result = serde_json::Value::String(verdict);
+        }
+    }
+
+    let Some(list) = value
+        .get_mut("list")
+        .and_then(serde_json::Value::as_array_mut)
+    else {
+        return;
+    };
+    for item in list {
+        if let Some(severity) = item.get_mut("severity") {
+            if let Some(text) = severity.as_str() {
+                *severity = serde_json::Value::String(text.trim().to_lowercase());
+            }
+        }
+    }
+}
+
+/// 宽松解析，作为严格解析因语法问题失败后的补救：容忍尾随逗号，
+/// 以及 `list`、`meme_comment` 为 null 或缺失。修补后的结果同样按 Schema 校验取值。
+pub fn parse_lenient(content: &str) -> Result<AiCheckResult> {
+    let cleaned = remove_trailing_commas(content.trim());
+    let json_str = extract_json(&cleaned, "result").unwrap_or_else(|| find_json_bounds(&cleaned));
+
+    let mut value: serde_json::Value =
+        serde_json::from_str(json_str).context(Msg::JsonSyntax.text())?;
+    if !value.is_object() {
+        anyhow::bail!(Msg::NotJsonObject.text());
+    }
+    fill_optional(&mut value);
+
+    from_validated(value, &schema::lenient_check_result_schema())
+}
+
+/// 未启用结构化输出时模型常把可选字段写成 null 或省略：`list` 补为空数组，
+/// null 的 `meme_comment` 去掉。需要在 Schema 校验之前处理，否则会被当作取值错误。
+fn fill_optional(value: &mut serde_json::Value) {
+    let Some(object) = value.as_object_mut() else {
+        return;
+    };
+    if object.get("list").is_none_or(serde_json::Value::is_null) {
+        object.insert("list".to_string(), serde_json::Value::Array(Vec::new()));
+    }
+    if object
+        .get("meme_comment")
+        .is_some_and(serde_json::Value::is_null)
+    {
+        object.remove("meme_comment");
+    }
+}
+
+/// 删除 `}` 或 `]` 前多余的逗号，字符串内部的内容保持不变。
+fn remove_trailing_commas(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in s.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if c == ',' {
+            let next = s[i + 1..].trim_start().chars().next();
+            if matches!(next, Some('}') | Some(']')) {
+                continue;
+            }
+        }
+        out.push(c);
+    }
+
+    out
+}
+
+/// 解析分流模型的输出，容忍前后的说明文字和尾随逗号。
+pub fn parse_triage(content: &str) -> Result<Triage> {
+    let cleaned = remove_trailing_commas(content.trim());
+    let json_str = extract_json(&cleaned, "risk").context(Msg::TriageJsonMissing.text())?;
+    serde_json::from_str(json_str).context(Msg::TriageJsonInvalid.text())
+}
+
+/// 在可能夹杂说明文字的输出中找到包含 `key` 字段的 JSON 对象。
+///
+/// 依次尝试整段内容、代码块内容，以及每个 `{` 开始的括号配对片段，
+/// 返回第一个能解析且包含该字段的对象。
+fn extract_json<'a>(content: &'a str, key: &str) -> Option<&'a str> {
+    let is_result = |candidate: &str| {
+        serde_json::from_str::<serde_json::Value>(candidate)
+            .map(|v| v.get(key).is_some())
+            .unwrap_or(false)
+    };
+
+    let fenced = strip_code_fence(content);
+    if is_result(fenced) {
+        return Some(fenced);
+    }
+
+    content
+        .match_indices('{')
+        .filter_map(|(start, _)| balanced_object(&content[start..]))
+        .find(|candidate| is_result(candidate))
+}
+
+/// 返回从开头的 `{` 到与之配对的 `}` 的片段，忽略字符串中的括号。
+fn balanced_object(s: &str) -> Option<&str> {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (i, c) in s.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match c {
+            '"' => in_string = true,
+            '{' => depth += 1,
+            '}' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return Some(&s[..=i]);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// 去掉模型有时会包裹在外层的 ``` 代码块标记。
+pub fn strip_code_fence(content: &str) -> &str {
+    let trimmed = content.trim();
+    match trimmed.strip_prefix("```") {
+        Some(rest) => {
+            // 跳过 ``` 后面的语言标记所在行
+            let body = rest.split_once('\n').map(|(_, b)| b).unwrap_or("");
+            body.trim_end().strip_suffix("```").unwrap_or(body).trim()
+        }
+        None => trimmed,
+    }
+}
+
+fn find_json_bounds(s: &str) -> &str {
+    let start = s.find('{').unwrap_or(0);
+    let end = s.rfind('}').map(|i| i + 1).unwrap_or(s.len());
+    &s[start..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ISSUE: &str = r#"{"severity": "HIGH", "perspective": "security", "description": "d", "suggestion": "s", "location": "a.rs:1"}"#;
+
+    #[test]
+    fn null_list_becomes_empty() {
+        let result = parse_check_result(
+            r#"{"result": "YES", "meme_comment": "ok", "list": null}"#,
+            false,
+        )
+        .unwrap();
+        assert!(result.is_approved());
+        assert!(result.list.is_empty());
+    }
+
+    #[test]
+    fn missing_list_becomes_empty() {
+        let result = parse_check_result(r#"{"result": "YES"}"#, false).unwrap();
+        assert!(result.list.is_empty());
+        assert!(result.meme_comment.is_none());
+    }
+
+    #[test]
+    fn null_meme_comment_is_dropped() {
+        let content = format!(
+            r#"{{"result": "NO", "meme_comment": null, "list": [{}]}}"#,
+            ISSUE
+        );
+        let result = parse_check_result(&content, false).unwrap();
+        assert!(result.meme_comment.is_none());
+        assert_eq!(result.list[0].severity, "high");
+    }
+
+    #[test]
+    fn fenced_json_is_accepted() {
+        let content = "```json\n{\"result\": \"YES\", \"meme_comment\": \"ok\", \"list\": []}\n```";
+        assert!(parse_check_result(content, false).unwrap().is_approved());
+        assert!(parse_check_result(content, true).unwrap().is_approved());
+    }
+
+    #[test]
+    fn trailing_prose_is_ignored() {
+        let content = format!(
+            "审查结果如下：\n{{\"result\": \"NO (rejected)\", \"list\": [{}]}}\n以上是全部问题，共 1 个 {{}}。",
+            ISSUE
+        );
+        let result = parse_check_result(&content, false).unwrap();
+        assert!(!result.is_approved());
+        assert_eq!(result.list.len(), 1);
+    }
+
+    #[test]
+    fn trailing_commas_are_repaired_by_lenient_parse() {
+        let content = r#"{"result": "YES", "list": [], "meme_comment": null,}"#;
+        assert!(parse_check_result(content, false).is_err());
+        assert!(parse_lenient(content).unwrap().is_approved());
+    }
+
+    #[test]
+    fn invalid_values_are_schema_errors() {
+        let content = r#"{"result": "MAYBE", "list": null}"#;
+        let err = parse_check_result(content, false).unwrap_err();
+        assert!(err.downcast_ref::<SchemaError>().is_some());
+        assert!(parse_lenient(content).is_err());
+    }
+
+    #[test]
+    fn strict_mode_requires_every_field() {
+        let err = parse_check_result(r#"{"result": "YES", "list": null}"#, true).unwrap_err();
+        assert!(err.downcast_ref::<SchemaError>().is_some());
+    }
+}
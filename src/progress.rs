@@ -0,0 +1,113 @@
+use crate::i18n::Msg;
+use std::io::{IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+const FRAMES: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+#[derive(Default)]
+struct Counters {
+    tokens: AtomicUsize,
+    stopped: AtomicBool,
+}
+
+/// 本次运行中已审查完成的 diff 块数和总块数，由调用方在逐块审查时更新。
+static CHUNKS_DONE: AtomicUsize = AtomicUsize::new(0);
+static CHUNKS_TOTAL: AtomicUsize = AtomicUsize::new(0);
+
+/// 更新 diff 块的审查进度；总块数不超过 1 时进度行不显示块数。
+pub fn set_chunks(done: usize, total: usize) {
+    CHUNKS_DONE.store(done, Ordering::Relaxed);
+    CHUNKS_TOTAL.store(total, Ordering::Relaxed);
+}
+
+/// 等待 AI 响应时的单行进度提示（spinner + diff 块进度 + 已接收的 Token 数）。
+///
+/// 标准输出不是 TTY 时（例如被 CI 或 IDE 捕获）不输出任何内容。
+pub struct Progress {
+    counters: Arc<Counters>,
+    ticker: Option<JoinHandle<()>>,
+}
+
+impl Progress {
+    pub fn start(label: &str) -> Self {
+        let counters = Arc::new(Counters::default());
+
+        let ticker = std::io::stdout().is_terminal().then(|| {
+            let counters = Arc::clone(&counters);
+            let label = label.to_string();
+            tokio::spawn(async move {
+                let started = Instant::now();
+                let mut interval = tokio::time::interval(Duration::from_millis(100));
+                for frame in FRAMES.iter().cycle() {
+                    interval.tick().await;
+                    if counters.stopped.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    let mut line = format!("\r\x1b[2K{} {}", frame, label);
+                    let total = CHUNKS_TOTAL.load(Ordering::Relaxed);
+                    if total > 1 {
+                        let done = CHUNKS_DONE.load(Ordering::Relaxed);
+                        line.push_str(&format!(
+                            " [{}]",
+                            Msg::ProgressChunks.fill(&[
+                                ("done", done.to_string()),
+                                ("total", total.to_string()),
+                            ])
+                        ));
+                    }
+                    let tokens = counters.tokens.load(Ordering::Relaxed);
+                    if tokens > 0 {
+                        line.push(' ');
+                        line.push_str(&Msg::ProgressTokens.fill(&[("tokens", tokens.to_string())]));
+                    }
+                    line.push_str(&format!(" ({:.1}s)", started.elapsed().as_secs_f32()));
+
+                    let mut stdout = std::io::stdout();
+                    let _ = stdout.write_all(line.as_bytes());
+                    let _ = stdout.flush();
+                }
+            })
+        });
+
+        Self { counters, ticker }
+    }
+
+    /// 记录流式输出中新收到的 Token 数。
+    pub fn received(&self, tokens: usize) {
+        self.counters.tokens.fetch_add(tokens, Ordering::Relaxed);
+    }
+
+    /// 服务端报告了一次请求准确的输出 Token 数时，用它替换本地对这次请求的估算。
+    /// 多个并发请求共用同一个进度行时，其他请求已累计的数量不受影响。
+    pub fn correct_tokens(&self, estimated: usize, actual: usize) {
+        if actual >= estimated {
+            self.counters.tokens.fetch_add(actual - estimated, Ordering::Relaxed);
+        } else {
+            self.counters.tokens.fetch_sub(estimated - actual, Ordering::Relaxed);
+        }
+    }
+
+    /// 停止 spinner 并清除进度行。
+    pub fn finish(mut self) {
+        self.stop();
+    }
+
+    fn stop(&mut self) {
+        if let Some(ticker) = self.ticker.take() {
+            self.counters.stopped.store(true, Ordering::Relaxed);
+            ticker.abort();
+            print!("\r\x1b[2K");
+            let _ = std::io::stdout().flush();
+        }
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        // 出错提前返回时也要清掉进度行，避免和错误信息混在一起
+        self.stop();
+    }
+}
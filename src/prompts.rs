@@ -1,114 +1,561 @@
 use crate::config::Config;
+use crate::context::{self, ProjectContext};
+use crate::i18n::{Locale, Msg};
+use crate::{diff, git, secrets, template};
+use anyhow::{Context, Result};
+use colored::*;
 use serde_json::json;
+use std::path::PathBuf;
 
-pub fn generate_system_prompt(config: &Config) -> String {
-    let mut rules = serde_json::Map::new();
-    let mut perspectives = vec!["general".to_string()];
-
-    // 通用检查总是包含的
-    rules.insert("general".to_string(), json!({
-        "name": "通用检查:",
-        "checks": [
-            "代码中的潜在Bug",
-            "代码的可读性",
-            "改进建议",
+/// 内置的中文审查系统提示词模板，可以用 `AI_CHECK_SYSTEM_TEMPLATE` 指向的文件替换。
+const SYSTEM_TEMPLATE_ZH: &str = r#"{
+  "system": "你是一位专业的代码审查专家，正在分析 git diff -U0 格式的代码变更。你的主要关注点应是新增和修改的代码部分，忽略已删除的部分。请严格按照以下维度进行审查，不要引入无关的视角：",
+  "instruction": "从这些视角进行分析",
+  "rules": {{rules}},
+  "project_context": {{context}},
+  "response": {
+    "requirement": "输出要求：\n请返回包含以下字段的 JSON：",
+    "fields": {
+      "result": "只能是字符串 \"YES\" 或 \"NO\"，不要附加其他文字：有高严重性 (high severity) 问题时为 \"NO\"（拒绝），否则为 \"YES\"（通过）",
+      "meme_comment": "使用中国网络热梗对代码进行简短、幽默且犀利的整体评价（可以不用友好）",
+      "list": "发现的问题列表，包含以下详情："
+    },
+    "itemFields": {
+      "severity": "high/medium/low",
+      "perspective": "{{perspectives}}",
+      "description": "用{{language}}描述问题",
+      "suggestion": "用{{language}}给出修复建议",
+      "location": "文件路径和行号，格式为：'path:line_number' (例如 src/utils.js:15)",
+      "patch": "可选：修复该问题的补丁，unified diff 格式，以 '--- a/<path>' 和 '+++ b/<path>' 开头，基于暂存区中的文件内容，@@ 行号与上面的 diff 一致；只修改与该问题相关的行，没有把握时返回 null"
+    }
+  }
+}
+"#;
+
+/// 内置的英文审查系统提示词模板，输出格式与中文模板相同。
+const SYSTEM_TEMPLATE_EN: &str = r#"{
+  "system": "You are an expert code reviewer analysing code changes in git diff -U0 format. Focus on added and modified code and ignore removed code. Review strictly along the following dimensions and do not introduce unrelated perspectives:",
+  "instruction": "Analyse from these perspectives",
+  "rules": {{rules}},
+  "project_context": {{context}},
+  "response": {
+    "requirement": "Output requirements:\nReturn JSON with the following fields:",
+    "fields": {
+      "result": "exactly the string \"YES\" or \"NO\" with no other text: \"NO\" (rejected) if there is any high severity issue, otherwise \"YES\" (approved)",
+      "meme_comment": "A short, witty and sharp overall verdict on the code in the style of an internet meme (it does not have to be friendly)",
+      "list": "List of issues found, each with the following details:"
+    },
+    "itemFields": {
+      "severity": "high/medium/low",
+      "perspective": "{{perspectives}}",
+      "description": "Describe the issue in {{language}}",
+      "suggestion": "Suggest a fix in {{language}}",
+      "location": "File path and line number in the form 'path:line_number' (e.g. src/utils.js:15)",
+      "patch": "Optional: a patch fixing the issue in unified diff format, starting with '--- a/<path>' and '+++ b/<path>', based on the staged file content, with @@ line numbers matching the diff above; change only the lines related to the issue and return null when unsure"
+    }
+  }
+}
+"#;
+
+/// 内置的审查用户消息模板，可以用 `AI_CHECK_USER_TEMPLATE` 指向的文件替换。
+const USER_TEMPLATE_ZH: &str = "这是需要审查的 git diff:\n\n{{diff}}";
+const USER_TEMPLATE_EN: &str = "Here is the git diff to review:\n\n{{diff}}";
+
+/// 界面语言对应的内置系统提示词和用户消息模板。
+fn builtin_templates(locale: Locale) -> (&'static str, &'static str) {
+    match locale {
+        Locale::ZhCn => (SYSTEM_TEMPLATE_ZH, USER_TEMPLATE_ZH),
+        Locale::En => (SYSTEM_TEMPLATE_EN, USER_TEMPLATE_EN),
+    }
+}
+
+/// 用户消息中 diff 的占位符，逐块发送时才替换。
+const DIFF_VAR: &str = "{{diff}}";
+
+/// 一份提示词模板；`source` 为 None 表示内置模板。
+pub struct Template {
+    pub text: String,
+    pub source: Option<PathBuf>,
+}
+
+impl Template {
+    pub fn source_label(&self) -> String {
+        match &self.source {
+            Some(path) => format!("{:?}", path),
+            None => Msg::Builtin.to_string(),
+        }
+    }
+}
+
+/// 渲染好的审查提示词。用户消息中的 `{{diff}}` 留到发送每个 diff 块时再替换。
+#[derive(Clone)]
+pub struct ReviewPrompt {
+    pub system: String,
+    user: String,
+}
+
+impl ReviewPrompt {
+    pub fn user_message(&self, diff: &str) -> String {
+        match self.user.split_once(DIFF_VAR) {
+            Some((before, after)) => format!("{}{}{}", before, diff, after),
+            None => format!("{}\n\n{}", self.user, diff),
+        }
+    }
+
+    /// 用户消息中 diff 以外的部分，切分 diff 时需要计入预算。
+    pub fn user_overhead(&self) -> String {
+        self.user_message("")
+    }
+}
+
+/// 读取生效的系统提示词和用户消息模板：配置的文件存在时使用文件，否则使用内置模板。
+pub fn load_templates(config: &Config) -> Result<(Template, Template)> {
+    let (system, user) = builtin_templates(config.prompt_lang);
+    Ok((
+        load_template(config, &config.system_template, system)?,
+        load_template(config, &config.user_template, user)?,
+    ))
+}
+
+fn load_template(config: &Config, path: &str, builtin: &str) -> Result<Template> {
+    let builtin = Template {
+        text: builtin.to_string(),
+        source: None,
+    };
+    if path.is_empty() {
+        return Ok(builtin);
+    }
+
+    let file = git::read_repo_file(config, path)
+        .with_context(|| Msg::TemplateReadFailed.fill(&[("path", format!("{:?}", path))]))?;
+    Ok(match file {
+        Some((text, source)) => Template {
+            text,
+            source: Some(source),
+        },
+        None => builtin,
+    })
+}
+
+/// 生成审查提示词：用配置、项目上下文和本次 diff 涉及的文件渲染模板。
+pub fn review_prompt(
+    config: &Config,
+    contexts: &[ProjectContext],
+    diff_text: &str,
+) -> Result<ReviewPrompt> {
+    let (system, user) = load_templates(config)?;
+    if !user.text.contains(DIFF_VAR) {
+        eprintln!(
+            "{} {}",
+            Msg::Warning.text().yellow(),
+            Msg::UserTemplateNoDiff.fill(&[("source", user.source_label())])
+        );
+    }
+
+    let vars = variables(config, contexts, diff_text);
+    Ok(ReviewPrompt {
+        system: template::render(&system.text, &vars),
+        user: template::render(&user.text, &vars),
+    })
+}
+
+/// 模板中可用的变量（`{{diff}}` 除外）。
+fn variables(
+    config: &Config,
+    contexts: &[ProjectContext],
+    diff_text: &str,
+) -> Vec<(&'static str, String)> {
+    let (rules, perspectives) = review_rules(config);
+
+    let context = if contexts.is_empty() {
+        "null".to_string()
+    } else {
+        let documents: Vec<_> = contexts
+            .iter()
+            .map(|c| {
+                json!({
+                    "scope": if c.scope.is_empty() { "/" } else { c.scope.as_str() },
+                    "content": c.content,
+                })
+            })
+            .collect();
+        let instruction = match config.prompt_lang {
+            Locale::ZhCn => "以下是项目的背景信息和约定（技术栈、架构、禁止的写法、编码规范等），审查时以此为准，违反约定的代码按问题报告。每份文档只适用于 scope 目录下的文件，子目录的约定与上级目录冲突时以子目录为准",
+            Locale::En => "Project background and conventions (tech stack, architecture, forbidden patterns, coding standards, etc.). Treat them as authoritative and report code that violates them as issues. Each document applies only to files under its scope directory; when a subdirectory's conventions conflict with a parent's, the subdirectory wins",
+        };
+        serde_json::to_string_pretty(&json!({
+            "instruction": instruction,
+            "documents": documents,
+        }))
+        .unwrap()
+    };
+
+    vec![
+        ("language", config.language.clone()),
+        ("perspectives", perspectives.join("/")),
+        ("rules", serde_json::to_string_pretty(&rules).unwrap()),
+        ("context", context),
+        ("files", diff::files(diff_text).join("\n")),
+        ("branch", git::current_branch().unwrap_or_default()),
+    ]
+}
+
+/// 各检查视角的规则：视角、名称、检查项和严重程度说明。
+type Rule = (&'static str, &'static str, &'static [&'static str], &'static str);
+
+const RULES_ZH: &[Rule] = &[
+    (
+        "general",
+        "通用检查:",
+        &["代码中的潜在Bug", "代码的可读性", "改进建议"],
+        "严重问题使用 high，中等问题使用 medium，轻微建议使用 low",
+    ),
+    (
+        "security",
+        "安全性:",
+        &["XSS 漏洞", "CSRF 保护", "CORS 配置", "第三方脚本安全", "多线程下的潜在问题"],
+        "严重漏洞使用 high，潜在风险使用 medium",
+    ),
+    (
+        "performance",
+        "性能:",
+        &["算法变更的影响", "内存使用模式", "I/O 操作变更", "并发修改"],
+        "严重瓶颈（如死循环、栈溢出等）使用 high，优化机会使用 medium",
+    ),
+    (
+        "style",
+        "代码风格:",
+        &["命名一致性", "代码组织变更", "文档更新", "风格指南遵循情况"],
+        "风格建议使用 low",
+    ),
+    (
+        "database",
+        "数据库:",
+        &[
+            "SQL 注入漏洞",
+            "SQL 语句正确性",
+            "查询性能优化",
+            "事务使用正确性",
+            "数据库连接管理",
+            "数据一致性",
+        ],
+        "严重漏洞（如 SQL 注入、数据库连接泄露）使用 high，性能问题使用 medium，规范问题使用 low",
+    ),
+];
+
+const RULES_EN: &[Rule] = &[
+    (
+        "general",
+        "General:",
+        &["Potential bugs", "Readability", "Suggested improvements"],
+        "Use high for serious problems, medium for moderate ones and low for minor suggestions",
+    ),
+    (
+        "security",
+        "Security:",
+        &[
+            "XSS vulnerabilities",
+            "CSRF protection",
+            "CORS configuration",
+            "Third-party script safety",
+            "Multithreading hazards",
+        ],
+        "Use high for serious vulnerabilities and medium for potential risks",
+    ),
+    (
+        "performance",
+        "Performance:",
+        &[
+            "Impact of algorithm changes",
+            "Memory usage patterns",
+            "I/O changes",
+            "Concurrent modification",
         ],
-        "severity_guidance": "严重问题使用 high，中等问题使用 medium，轻微建议使用 low"
-    }));
+        "Use high for severe bottlenecks (infinite loops, stack overflows, etc.) and medium for optimisation opportunities",
+    ),
+    (
+        "style",
+        "Code style:",
+        &[
+            "Naming consistency",
+            "Code organisation changes",
+            "Documentation updates",
+            "Style guide compliance",
+        ],
+        "Use low for style suggestions",
+    ),
+    (
+        "database",
+        "Database:",
+        &[
+            "SQL injection",
+            "SQL correctness",
+            "Query performance",
+            "Transaction usage",
+            "Connection management",
+            "Data consistency",
+        ],
+        "Use high for serious vulnerabilities (SQL injection, leaked connections), medium for performance problems and low for convention issues",
+    ),
+];
+
+/// 按配置启用的检查视角生成审查规则，返回规则和视角列表。
+fn review_rules(config: &Config) -> (serde_json::Map<String, serde_json::Value>, Vec<String>) {
+    let enabled = |perspective: &str| match perspective {
+        "security" => config.check_security,
+        "performance" => config.check_performance,
+        "style" => config.check_style,
+        "database" => config.check_sql,
+        // 通用检查总是包含的
+        _ => true,
+    };
+    let table = match config.prompt_lang {
+        Locale::ZhCn => RULES_ZH,
+        Locale::En => RULES_EN,
+    };
 
-    if config.check_security {
-        perspectives.push("security".to_string());
+    let mut rules = serde_json::Map::new();
+    let mut perspectives = Vec::new();
+    for (perspective, name, checks, guidance) in table {
+        if !enabled(perspective) {
+            continue;
+        }
+        perspectives.push(perspective.to_string());
         rules.insert(
-            "security".to_string(),
+            perspective.to_string(),
             json!({
-                "name": "安全性:",
-                "checks": [
-                    "XSS 漏洞",
-                    "CSRF 保护",
-                    "CORS 配置",
-                    "第三方脚本安全",
-                    "多线程下的潜在问题"
-                ],
-                "severity_guidance": "严重漏洞使用 high，潜在风险使用 medium"
+                "name": name,
+                "checks": checks,
+                "severity_guidance": guidance,
             }),
         );
     }
 
-    if config.check_performance {
-        perspectives.push("performance".to_string());
-        rules.insert("performance".to_string(), json!({
-            "name": "性能:",
-            "checks": [
-                "算法变更的影响",
-                "内存使用模式",
-                "I/O 操作变更",
-                "并发修改",
-            ],
-            "severity_guidance": "严重瓶颈（如死循环、栈溢出等）使用 high，优化机会使用 medium"
-        }));
+    (rules, perspectives)
+}
+
+/// `prompt print`：输出当前生效的提示词。默认用暂存区的变更渲染，`raw` 时输出模板原文。
+pub fn print(config: &Config, raw: bool) -> Result<()> {
+    let (system, user) = load_templates(config)?;
+
+    if raw {
+        println!("{} ({})", Msg::SystemTemplate.text().cyan().bold(), system.source_label());
+        println!("{}\n", system.text.trim_end());
+        println!("{} ({})", Msg::UserTemplate.text().cyan().bold(), user.source_label());
+        println!("{}", user.text.trim_end());
+        return Ok(());
     }
 
-    if config.check_style {
-        perspectives.push("style".to_string());
-        rules.insert(
-            "style".to_string(),
-            json!({
-                "name": "代码风格:",
-                "checks": [
-                    "命名一致性",
-                    "代码组织变更",
-                    "文档更新",
-                    "风格指南遵循情况"
-                ],
-                "severity_guidance": "风格建议使用 low"
-            }),
-        );
+    // 与实际发送的内容一致：密钥已脱敏
+    let mut diff_text = git::get_staged_diff(config).unwrap_or_default();
+    if config.redact_secrets {
+        diff_text = secrets::scan(&diff_text).redacted;
     }
+    let contexts = context::load(config, &diff_text);
+    let prompt = review_prompt(config, &contexts, &diff_text)?;
 
-    if config.check_sql {
-        perspectives.push("database".to_string());
-        rules.insert(
-            "database".to_string(),
-            json!({
-                "name": "数据库:",
-                "checks": [
-                    "SQL 注入漏洞",
-                    "SQL 语句正确性",
-                    "查询性能优化",
-                    "事务使用正确性",
-                    "数据库连接管理",
-                    "数据一致性",
-                ],
-                "severity_guidance": "严重漏洞（如 SQL 注入、数据库连接泄露）使用 high，性能问题使用 medium，规范问题使用 low"
-            }),
+    println!("{} ({})", Msg::SystemPrompt.text().cyan().bold(), system.source_label());
+    println!("{}\n", prompt.system.trim_end());
+    println!("{} ({})", Msg::UserMessage.text().cyan().bold(), user.source_label());
+    println!("{}", prompt.user_message(&diff_text).trim_end());
+    Ok(())
+}
+
+/// 提交信息审查的系统提示词，按 `prompt_lang` 选择语言。
+pub fn generate_commit_msg_prompt(config: &Config) -> String {
+    let prompt_structure = match config.prompt_lang {
+        Locale::ZhCn => commit_msg_prompt_zh(config),
+        Locale::En => commit_msg_prompt_en(config),
+    };
+    serde_json::to_string_pretty(&prompt_structure).unwrap()
+}
+
+fn commit_msg_prompt_zh(config: &Config) -> serde_json::Value {
+    let mut checks = vec![
+        "提交信息是否准确概括了暂存的变更".to_string(),
+        format!("标题行（第一行）不超过 {} 个字符", config.commit_max_subject),
+        "标题与正文之间是否有空行".to_string(),
+    ];
+
+    if config.commit_convention == "conventional" {
+        checks.push(
+            "标题是否符合 Conventional Commits 规范：'<type>(<scope>): <subject>'，type 为 feat/fix/docs/style/refactor/perf/test/build/ci/chore/revert 之一".to_string(),
         );
     }
 
-    let prompt_structure = json!({
-        "system": "你是一位专业的代码审查专家，正在分析 git diff -U0 格式的代码变更。你的主要关注点应是新增和修改的代码部分，忽略已删除的部分。请严格按照以下维度进行审查，不要引入无关的视角：",
-        "instruction": "从这些视角进行分析",
-        "rules": rules,
+    if !config.commit_language.is_empty() {
+        checks.push(format!("提交信息是否使用{}书写", config.commit_language));
+    }
+
+    json!({
+        "system": "你是一位严格的代码提交规范审查专家，正在审查一条 git 提交信息。你会同时收到暂存变更的统计摘要，用于判断提交信息是否与实际改动相符。",
+        "instruction": "按以下规则检查提交信息",
+        "rules": {
+            "commit_message": {
+                "name": "提交信息规范:",
+                "checks": checks,
+                "severity_guidance": "违反格式规范或与变更明显不符使用 high，表述不清使用 medium，措辞建议使用 low"
+            }
+        },
         "response": {
             "requirement": "输出要求：\n请返回包含以下字段的 JSON：",
             "fields": {
-                "result": "如果有高严重性 (high severity) 问题，返回 NO (rejected)；否则返回 YES (approved)",
-                "meme_comment": "使用中国网络热梗对代码进行简短、幽默且犀利的整体评价（可以不用友好）",
+                "result": "只能是字符串 \"YES\" 或 \"NO\"，不要附加其他文字：有高严重性 (high severity) 问题时为 \"NO\"（拒绝），否则为 \"YES\"（通过）",
+                "meme_comment": "使用中国网络热梗对这条提交信息进行简短、幽默且犀利的评价（可以不用友好）",
                 "list": "发现的问题列表，包含以下详情："
             },
             "itemFields": {
                 "severity": "high/medium/low",
-                "perspective": perspectives.join("/"),
+                "perspective": "commit_message",
                 "description": format!("用{}描述问题", config.language),
-                "suggestion": format!("用{}给出修复建议", config.language),
-                "location": "文件路径和行号，格式为：'path:line_number' (例如 src/utils.js:15)"
+                "suggestion": format!("用{}给出修改后的提交信息或修改建议", config.language),
+                "location": "问题所在的行，格式为：'COMMIT_EDITMSG:line_number'"
+            }
+        }
+    })
+}
+
+fn commit_msg_prompt_en(config: &Config) -> serde_json::Value {
+    let mut checks = vec![
+        "Does the message accurately summarise the staged changes".to_string(),
+        format!(
+            "The subject line (first line) is at most {} characters",
+            config.commit_max_subject
+        ),
+        "Is there a blank line between the subject and the body".to_string(),
+    ];
+
+    if config.commit_convention == "conventional" {
+        checks.push(
+            "Does the subject follow Conventional Commits: '<type>(<scope>): <subject>', where type is one of feat/fix/docs/style/refactor/perf/test/build/ci/chore/revert".to_string(),
+        );
+    }
+
+    if !config.commit_language.is_empty() {
+        checks.push(format!(
+            "Is the message written in {}",
+            config.commit_language
+        ));
+    }
+
+    json!({
+        "system": "You are a strict reviewer of commit conventions, reviewing a git commit message. You also receive a summary of the staged changes to judge whether the message matches the actual changes.",
+        "instruction": "Check the commit message against the following rules",
+        "rules": {
+            "commit_message": {
+                "name": "Commit message conventions:",
+                "checks": checks,
+                "severity_guidance": "Use high for format violations or a message that clearly does not match the changes, medium for unclear wording and low for phrasing suggestions"
+            }
+        },
+        "response": {
+            "requirement": "Output requirements:\nReturn JSON with the following fields:",
+            "fields": {
+                "result": "exactly the string \"YES\" or \"NO\" with no other text: \"NO\" (rejected) if there is any high severity issue, otherwise \"YES\" (approved)",
+                "meme_comment": "A short, witty and sharp verdict on this commit message in the style of an internet meme (it does not have to be friendly)",
+                "list": "List of issues found, each with the following details:"
+            },
+            "itemFields": {
+                "severity": "high/medium/low",
+                "perspective": "commit_message",
+                "description": format!("Describe the issue in {}", config.language),
+                "suggestion": format!("Give a corrected commit message or a suggestion in {}", config.language),
+                "location": "The line with the issue in the form 'COMMIT_EDITMSG:line_number'"
             }
         }
-    });
+    })
+}
 
-    // 将结构转换为可读字符串以指导 AI
-    // 我们希望 AI 将此结构视为其指令。
-    // 用户的提示是一个 JS 对象，所以我们可以将此 JSON 转储为系统消息。
-    // 或者我们可以将其格式化得很好。AI 通常能很好地理解 JSON 指令。
+/// 生成提交信息的系统提示词，按 `prompt_lang` 选择语言。
+pub fn generate_message_prompt(config: &Config) -> String {
+    let template = config.message_template.replace("\\n", "\n");
+    let conventional = config.commit_convention == "conventional";
+
+    let prompt_structure = match config.prompt_lang {
+        Locale::ZhCn => {
+            let mut requirements = vec![
+                format!("严格遵循以下模板：\n{}", template),
+                format!("使用{}书写", config.language),
+                format!("标题行不超过 {} 个字符", config.commit_max_subject),
+                "标题概括本次变更的目的，正文说明主要改动点；改动很小时可以省略正文".to_string(),
+            ];
+            if conventional {
+                requirements.push(
+                    "type 取 feat/fix/docs/style/refactor/perf/test/build/ci/chore/revert 之一"
+                        .to_string(),
+                );
+            }
+            json!({
+                "system": "你是一位经验丰富的开发者，需要根据暂存区的 git diff 为本次提交撰写提交信息。",
+                "requirements": requirements,
+                "response": "只输出提交信息本身，不要使用代码块，不要添加任何解释。"
+            })
+        }
+        Locale::En => {
+            let mut requirements = vec![
+                format!("Follow this template strictly:\n{}", template),
+                format!("Write in {}", config.language),
+                format!(
+                    "The subject line is at most {} characters",
+                    config.commit_max_subject
+                ),
+                "The subject states the purpose of the change and the body lists the main changes; omit the body for trivial changes".to_string(),
+            ];
+            if conventional {
+                requirements.push(
+                    "type is one of feat/fix/docs/style/refactor/perf/test/build/ci/chore/revert"
+                        .to_string(),
+                );
+            }
+            json!({
+                "system": "You are an experienced developer writing the commit message for this commit based on the staged git diff.",
+                "requirements": requirements,
+                "response": "Output only the commit message itself, without code fences or any explanation."
+            })
+        }
+    };
+
+    serde_json::to_string_pretty(&prompt_structure).unwrap()
+}
+
+/// 风险分流的系统提示词，按 `prompt_lang` 选择语言。
+pub fn generate_triage_prompt(config: &Config) -> String {
+    let prompt_structure = match config.prompt_lang {
+        Locale::ZhCn => json!({
+            "system": "你是一位代码审查分流助手，正在快速浏览 git diff -U0 格式的代码变更。你不需要找出具体问题，只需判断这段变更需要多仔细的审查。",
+            "instruction": "按以下标准评估风险等级",
+            "rules": {
+                "low": "只涉及文档、注释、测试、格式调整、依赖版本号或简单配置",
+                "medium": "普通业务逻辑变更、重构、公共接口调整",
+                "high": "认证与权限、加密与密钥、SQL 与数据迁移、并发与锁、支付与计费、输入校验与反序列化、内存安全（unsafe）"
+            },
+            "response": {
+                "requirement": "输出要求：\n请只返回包含以下字段的 JSON：",
+                "fields": {
+                    "risk": "low/medium/high",
+                    "categories": "涉及的风险类别数组，例如 [\"auth\", \"sql\", \"concurrency\"]，低风险时为 [\"docs\"]、[\"tests\"] 或 [\"config\"] 等",
+                    "reason": format!("用{}一句话说明判断依据", config.language)
+                }
+            }
+        }),
+        Locale::En => json!({
+            "system": "You are a code review triage assistant skimming code changes in git diff -U0 format. You do not need to find specific issues, only judge how carefully this change needs to be reviewed.",
+            "instruction": "Assess the risk level using the following criteria",
+            "rules": {
+                "low": "Only documentation, comments, tests, formatting, dependency versions or simple configuration",
+                "medium": "Ordinary business logic changes, refactoring, public interface changes",
+                "high": "Authentication and authorisation, cryptography and keys, SQL and data migrations, concurrency and locking, payments and billing, input validation and deserialisation, memory safety (unsafe)"
+            },
+            "response": {
+                "requirement": "Output requirements:\nReturn only JSON with the following fields:",
+                "fields": {
+                    "risk": "low/medium/high",
+                    "categories": "Array of the risk categories involved, e.g. [\"auth\", \"sql\", \"concurrency\"]; for low risk use [\"docs\"], [\"tests\"], [\"config\"] etc.",
+                    "reason": format!("One sentence in {} explaining the assessment", config.language)
+                }
+            }
+        }),
+    };
 
     serde_json::to_string_pretty(&prompt_structure).unwrap()
 }
@@ -0,0 +1,115 @@
+use crate::ai::{AiCheckResult, TokenUsage};
+use crate::config::Config;
+use crate::i18n::Msg;
+use colored::*;
+
+/// 打印 Token 使用情况、AI 锐评和问题列表。
+pub fn print_result(config: &Config, result: &AiCheckResult) {
+    println!("\n{}", Msg::AnalysisResult.text().bold().underline());
+
+    if !result.models.is_empty() {
+        println!("{} {}", Msg::ReviewModels.text().purple(), result.models.join(", "));
+    }
+
+    // 两阶段审查：每块的风险评估及最终使用的模型
+    if !result.triage.is_empty() {
+        println!("{}", Msg::Triage.text().purple());
+        for (i, decision) in result.triage.iter().enumerate() {
+            println!(
+                "  {}: {} [{}] → {}{} {}",
+                Msg::TriageChunk.fill(&[("n", (i + 1).to_string())]),
+                decision.risk,
+                decision.categories.join(", "),
+                decision.model,
+                if decision.escalated {
+                    Msg::DeepReview.text()
+                } else {
+                    ""
+                },
+                decision.reason.dimmed()
+            );
+        }
+    }
+
+    // 显示 Token 使用情况
+    if let Some(usage) = result.total_usage() {
+        println!(
+            "{} {}",
+            Msg::TokenUsage.text().purple(),
+            Msg::TokenTotals.fill(&[
+                ("input", usage.prompt_tokens.to_string()),
+                ("output", usage.completion_tokens.to_string()),
+                ("total", usage.total_tokens.to_string()),
+            ])
+        );
+
+        // 不止一个阶段时按阶段分别显示
+        let mut stages: Vec<(&str, TokenUsage)> = Vec::new();
+        for entry in &result.usage {
+            match stages.iter_mut().find(|(stage, _)| *stage == entry.stage) {
+                Some((_, total)) => total.add(&entry.usage),
+                None => stages.push((&entry.stage, entry.usage.clone())),
+            }
+        }
+        if stages.len() > 1 {
+            for (stage, u) in &stages {
+                println!(
+                    "  {}: {}",
+                    stage,
+                    Msg::StageTokens.fill(&[
+                        ("input", u.prompt_tokens.to_string()),
+                        ("output", u.completion_tokens.to_string()),
+                    ])
+                );
+            }
+        }
+
+        // 按各模型自己的价格计算；价格未知的模型不计入
+        let costs: Vec<f64> = result
+            .usage
+            .iter()
+            .filter_map(|entry| crate::usage::cost(config, &entry.model, &entry.usage))
+            .collect();
+        if !costs.is_empty() {
+            println!("{} ${:.4}", Msg::EstimatedCost.text().purple(), costs.iter().sum::<f64>());
+        }
+
+        // 显式配置的模型参数，便于复现本次结果
+        let params = crate::ai::model_params(config);
+        if !params.is_empty() {
+            println!("{} {}", Msg::ModelParams.text().purple(), serde_json::Value::Object(params));
+        }
+    }
+
+    // 显示 AI 锐评
+    if let Some(comment) = &result.meme_comment {
+        println!("\n{}", Msg::MemeComment.text().magenta().bold());
+        println!("{}", comment.italic());
+    }
+
+    for issue in &result.list {
+        let severity_color = match issue.severity.to_lowercase().as_str() {
+            "high" => "red",
+            "medium" => "yellow",
+            "low" => "green",
+            _ => "white",
+        };
+
+        println!(
+            "\n[{}] [{}] {}",
+            issue.severity.color(severity_color).bold(),
+            issue.perspective.cyan(),
+            issue.location.white().italic()
+        );
+        println!("{}{}", Msg::IssueDescription, issue.description);
+        println!("{}{}", Msg::IssueSuggestion, issue.suggestion);
+        if issue.patch.is_some() {
+            println!("{}{}", Msg::IssuePatch, Msg::PatchAvailable.text().green());
+        }
+        if !issue.models.is_empty() {
+            println!("{}{}", Msg::IssueModels, issue.models.join(", "));
+        }
+    }
+
+    println!();
+}
@@ -0,0 +1,118 @@
+use crate::config::Config;
+use crate::i18n::Msg;
+use crate::{filter, git};
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// 把指定的文件和目录整体当作新增内容，生成与 `git diff` 相同格式的伪 diff。
+///
+/// 目录会递归展开，跳过隐藏目录（如 `.git`）；文件按扩展名和排除规则过滤，
+/// 二进制文件直接忽略，非 UTF-8 的内容替换为 U+FFFD。
+pub fn pseudo_diff(config: &Config, paths: &[PathBuf]) -> Result<String> {
+    let mut files = Vec::new();
+    for path in paths {
+        if !path.exists() {
+            bail!(Msg::PathNotFound.fill(&[("path", format!("{:?}", path))]));
+        }
+        collect(path, &mut files)?;
+    }
+    // 同一个文件可能以不同的写法（相对路径、绝对路径）出现多次
+    let mut files: Vec<PathBuf> = files
+        .into_iter()
+        .map(|file| file.canonicalize().unwrap_or(file))
+        .collect();
+    files.sort();
+    files.dedup();
+
+    // 路径统一相对仓库根目录，不在仓库中时相对当前目录
+    let bases: Vec<PathBuf> = [
+        git::backend(config).work_dir().ok(),
+        std::env::current_dir().ok(),
+    ]
+    .into_iter()
+    .flatten()
+    .filter_map(|dir| dir.canonicalize().ok())
+    .collect();
+
+    let mut diff = String::new();
+    for file in files {
+        let name = display_name(&file, &bases);
+        if !filter::is_monitored(config, Path::new(&name)) {
+            continue;
+        }
+
+        let bytes = fs::read(&file)
+            .with_context(|| Msg::ReadFileFailed.fill(&[("path", format!("{:?}", file))]))?;
+        if bytes.contains(&0) {
+            continue;
+        }
+        let content = String::from_utf8_lossy(&bytes);
+        let lines: Vec<&str> = content.lines().collect();
+        if lines.is_empty() {
+            continue;
+        }
+
+        diff.push_str(&format!(
+            "diff --git a/{0} b/{0}\nnew file mode 100644\n--- /dev/null\n+++ b/{0}\n@@ -0,0 +1,{1} @@\n",
+            name,
+            lines.len()
+        ));
+        for line in lines {
+            diff.push('+');
+            diff.push_str(line);
+            diff.push('\n');
+        }
+    }
+
+    Ok(diff)
+}
+
+/// 递归收集文件。命令行中直接给出的路径是符号链接时照常读取；目录中的符号链接
+/// 一律跳过，避免链接成环导致无限递归，也避免读到目录之外的文件。
+fn collect(path: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    if !path.is_dir() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(path)
+        .with_context(|| Msg::ReadDirFailed.fill(&[("path", format!("{:?}", path))]))?
+    {
+        let entry = entry?;
+        // `DirEntry::file_type` 不跟随符号链接
+        let file_type = entry.file_type()?;
+        if file_type.is_symlink() {
+            continue;
+        }
+        if file_type.is_dir() {
+            if !entry.file_name().to_string_lossy().starts_with('.') {
+                collect(&entry.path(), files)?;
+            }
+            continue;
+        }
+        files.push(entry.path());
+    }
+    Ok(())
+}
+
+/// diff 中使用的路径：`path`（已规范化的绝对路径）相对 `bases` 中第一个包含它的目录，
+/// 统一为 `/` 分隔。
+///
+/// 文件在这些目录之外时去掉根目录和 `..`，保证 diff 头中不出现绝对路径或 `..`，
+/// 与 git 生成的 diff 一致。
+fn display_name(path: &Path, bases: &[PathBuf]) -> String {
+    let relative = bases
+        .iter()
+        .find_map(|base| path.strip_prefix(base).ok())
+        .unwrap_or(path);
+
+    relative
+        .components()
+        .filter_map(|part| match part {
+            Component::Normal(name) => Some(name.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
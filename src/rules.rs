@@ -0,0 +1,286 @@
+use crate::ai::Issue;
+use crate::config::Config;
+use crate::diff::{self, AddedLine};
+use crate::i18n::Msg;
+use crate::secrets;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+/// 本地规则的 ID，可通过 `AI_CHECK_DISABLED_RULES` 关闭。
+const MERGE_MARKER: &str = "merge-marker";
+const DEBUG_STATEMENT: &str = "debug-statement";
+const TODO_TICKET: &str = "todo-ticket";
+const LARGE_FILE: &str = "large-file";
+const TRAILING_WHITESPACE: &str = "trailing-whitespace";
+
+/// 各语言常见的调试输出：(适用的扩展名, 匹配模式)
+const DEBUG_PATTERNS: &[(&[&str], &str)] = &[
+    (&["js", "jsx", "ts", "tsx", "vue"], r"\bconsole\.(log|debug|trace)\s*\(|\bdebugger\s*;"),
+    (&["rs"], r"\bdbg!\s*\("),
+    (&["java", "kt"], r"\bSystem\.(out|err)\.print(ln)?\s*\(|\.printStackTrace\s*\(\s*\)"),
+    (&["py"], r"\b(breakpoint\s*\(\s*\)|pdb\.set_trace\s*\()"),
+    (&["php"], r"\b(var_dump|print_r|dd)\s*\("),
+    (&["go"], r"\bspew\.Dump\s*\("),
+];
+
+/// 在不依赖网络的情况下检查 diff 中新增的行，返回与 AI 结果格式相同的问题列表。
+pub fn check(config: &Config, diff_text: &str) -> Vec<Issue> {
+    if !config.local_rules {
+        return Vec::new();
+    }
+
+    let enabled = |id: &str| !config.disabled_rules.iter().any(|r| r == id);
+    let lines = diff::added_lines(diff_text);
+    let mut issues = Vec::new();
+
+    for line in &lines {
+        if enabled(MERGE_MARKER) && is_merge_marker(line.content) {
+            issues.push(issue(
+                "high",
+                MERGE_MARKER,
+                line,
+                Msg::RuleMergeMarker.text(),
+                Msg::RuleMergeMarkerFix.text(),
+            ));
+        }
+
+        if enabled(DEBUG_STATEMENT) && is_debug_statement(&line.file, line.content) {
+            issues.push(issue(
+                "medium",
+                DEBUG_STATEMENT,
+                line,
+                &Msg::RuleDebugStatement.fill(&[("code", line.content.trim().to_string())]),
+                Msg::RuleDebugStatementFix.text(),
+            ));
+        }
+
+        if enabled(TODO_TICKET) && is_todo_without_ticket(line.content) {
+            issues.push(issue(
+                "low",
+                TODO_TICKET,
+                line,
+                Msg::RuleTodoTicket.text(),
+                Msg::RuleTodoTicketFix.text(),
+            ));
+        }
+    }
+
+    // 以下两条规则按文件汇总，避免同一问题刷屏
+    let mut by_file: BTreeMap<&str, Vec<&AddedLine>> = BTreeMap::new();
+    for line in &lines {
+        by_file.entry(line.file.as_str()).or_default().push(line);
+    }
+
+    for (file, added) in by_file {
+        if enabled(LARGE_FILE) && config.max_file_lines > 0 && added.len() > config.max_file_lines {
+            issues.push(issue(
+                "medium",
+                LARGE_FILE,
+                added[0],
+                &Msg::RuleLargeFile.fill(&[
+                    ("file", file.to_string()),
+                    ("lines", added.len().to_string()),
+                    ("max", config.max_file_lines.to_string()),
+                ]),
+                Msg::RuleLargeFileFix.text(),
+            ));
+        }
+
+        if enabled(TRAILING_WHITESPACE) {
+            let trailing: Vec<&&AddedLine> = added
+                .iter()
+                .filter(|l| {
+                    let content = l.content.trim_end_matches('\r');
+                    content.trim_end() != content
+                })
+                .collect();
+            if let Some(first) = trailing.first() {
+                issues.push(issue(
+                    "low",
+                    TRAILING_WHITESPACE,
+                    first,
+                    &Msg::RuleTrailingWhitespace.fill(&[("count", trailing.len().to_string())]),
+                    Msg::RuleTrailingWhitespaceFix.text(),
+                ));
+            }
+        }
+    }
+
+    issues
+}
+
+/// 结果确定、足以不经 AI 直接拒绝提交的本地问题：合并冲突标记和固定格式的密钥。
+///
+/// 其他本地问题（包括启发式的密钥规则）与 AI 的结果合并后再决定是否拒绝。
+pub fn is_blocking(issue: &Issue) -> bool {
+    issue.severity.eq_ignore_ascii_case("high")
+        && [MERGE_MARKER, secrets::PERSPECTIVE].contains(&issue.perspective.as_str())
+}
+
+fn issue(
+    severity: &str,
+    rule: &str,
+    line: &AddedLine,
+    description: &str,
+    suggestion: &str,
+) -> Issue {
+    Issue {
+        severity: severity.to_string(),
+        perspective: rule.to_string(),
+        description: description.to_string(),
+        suggestion: suggestion.to_string(),
+        location: format!("{}:{}", line.file, line.line_no),
+        patch: None,
+        models: Vec::new(),
+    }
+}
+
+/// `=======` 也常见于 Markdown/RST 标题下划线，只识别两端的标记。
+fn is_merge_marker(content: &str) -> bool {
+    ["<<<<<<< ", ">>>>>>> ", "|||||||"]
+        .iter()
+        .any(|marker| content.starts_with(marker))
+        || content == "<<<<<<<"
+        || content == ">>>>>>>"
+}
+
+fn is_debug_statement(file: &str, content: &str) -> bool {
+    static PATTERNS: OnceLock<Vec<(&[&str], Regex)>> = OnceLock::new();
+    let patterns = PATTERNS.get_or_init(|| {
+        DEBUG_PATTERNS
+            .iter()
+            .map(|(exts, pattern)| (*exts, Regex::new(pattern).expect("invalid debug pattern")))
+            .collect()
+    });
+
+    let trimmed = content.trim_start();
+    if trimmed.starts_with("//") || trimmed.starts_with('#') {
+        return false;
+    }
+
+    let ext = file.rsplit_once('.').map(|(_, ext)| ext).unwrap_or_default();
+    patterns
+        .iter()
+        .any(|(exts, re)| exts.contains(&ext) && re.is_match(content))
+}
+
+fn is_todo_without_ticket(content: &str) -> bool {
+    static TODO: OnceLock<Regex> = OnceLock::new();
+    static TICKET: OnceLock<Regex> = OnceLock::new();
+    let todo = TODO.get_or_init(|| Regex::new(r"\b(TODO|FIXME|XXX)\b").unwrap());
+    let ticket = TICKET.get_or_init(|| Regex::new(r"\b[A-Z][A-Z0-9]+-\d+\b|#\d+\b").unwrap());
+
+    todo.is_match(content) && !ticket.is_match(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diff_of(file: &str, added: &[&str]) -> String {
+        let mut diff = format!(
+            "diff --git a/{file} b/{file}\n--- a/{file}\n+++ b/{file}\n@@ -0,0 +1,{} @@\n",
+            added.len()
+        );
+        for line in added {
+            diff.push_str(&format!("+{line}\n"));
+        }
+        diff
+    }
+
+    fn config() -> Config {
+        let mut config = Config::load().unwrap();
+        config.local_rules = true;
+        config.disabled_rules = Vec::new();
+        config.max_file_lines = 0;
+        config
+    }
+
+    fn found(config: &Config, diff: &str) -> Vec<(String, String, String)> {
+        check(config, diff)
+            .into_iter()
+            .map(|i| (i.perspective, i.severity, i.location))
+            .collect()
+    }
+
+    fn rule(id: &str, severity: &str, location: &str) -> (String, String, String) {
+        (id.to_string(), severity.to_string(), location.to_string())
+    }
+
+    #[test]
+    fn merge_markers_are_high_and_blocking() {
+        let diff = diff_of(
+            "a.rs",
+            &["<<<<<<< HEAD", "a", "=======", "b", ">>>>>>> topic"],
+        );
+        let issues = check(&config(), &diff);
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].location, "a.rs:1");
+        assert_eq!(issues[1].location, "a.rs:5");
+        assert!(issues.iter().all(is_blocking));
+        // Markdown 标题下划线不算冲突标记
+        assert!(check(&config(), &diff_of("a.md", &["Title", "======="])).is_empty());
+    }
+
+    #[test]
+    fn debug_statements_depend_on_file_type() {
+        let config = config();
+        assert_eq!(
+            found(&config, &diff_of("a.ts", &["ok();", "  console.log(x);"])),
+            [rule(DEBUG_STATEMENT, "medium", "a.ts:2")]
+        );
+        assert_eq!(
+            found(&config, &diff_of("a.rs", &["dbg!(x);"])),
+            [rule(DEBUG_STATEMENT, "medium", "a.rs:1")]
+        );
+        // 其他语言的写法和注释中的调用不报告
+        assert!(check(
+            &config,
+            &diff_of("a.py", &["console.log(x)", "# breakpoint()"])
+        )
+        .is_empty());
+        assert!(check(&config, &diff_of("a.rs", &["// dbg!(x);"])).is_empty());
+    }
+
+    #[test]
+    fn todo_needs_a_ticket() {
+        let diff = diff_of(
+            "a.rs",
+            &["// TODO: later", "// TODO(PROJ-12): ok", "// FIXME #42"],
+        );
+        assert_eq!(
+            found(&config(), &diff),
+            [rule(TODO_TICKET, "low", "a.rs:1")]
+        );
+    }
+
+    #[test]
+    fn large_files_and_trailing_whitespace_are_reported_once_per_file() {
+        let mut config = config();
+        config.max_file_lines = 2;
+        let diff = format!(
+            "{}{}",
+            diff_of("a.rs", &["a ", "b", "c\t"]),
+            diff_of("b.rs", &["d", "e\r"])
+        );
+        assert_eq!(
+            found(&config, &diff),
+            [
+                rule(LARGE_FILE, "medium", "a.rs:1"),
+                rule(TRAILING_WHITESPACE, "low", "a.rs:1"),
+            ]
+        );
+    }
+
+    #[test]
+    fn rules_can_be_disabled() {
+        let diff = diff_of("a.ts", &["console.log(x); // TODO"]);
+        let mut config = config();
+        assert_eq!(check(&config, &diff).len(), 2);
+        config.disabled_rules = vec![DEBUG_STATEMENT.to_string()];
+        assert_eq!(found(&config, &diff), [rule(TODO_TICKET, "low", "a.ts:1")]);
+        config.local_rules = false;
+        assert!(check(&config, &diff).is_empty());
+    }
+}
@@ -0,0 +1,190 @@
+use crate::i18n::Msg;
+use serde_json::{json, Value};
+
+/// `AiCheckResult` / `Issue` 对应的 JSON Schema，既用于 `response_format`，也用于校验模型输出。
+///
+/// 字段需要与 `ai::AiCheckResult` 和 `ai::Issue` 保持一致。
+pub fn check_result_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "result": { "type": "string", "enum": ["YES", "NO"] },
+            "meme_comment": { "type": "string" },
+            "list": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "severity": { "type": "string", "enum": ["high", "medium", "low"] },
+                        "perspective": { "type": "string" },
+                        "description": { "type": "string" },
+                        "suggestion": { "type": "string" },
+                        "location": { "type": "string" },
+                        "patch": { "type": ["string", "null"] }
+                    },
+                    "required": ["severity", "perspective", "description", "suggestion", "location", "patch"],
+                    "additionalProperties": false
+                }
+            }
+        },
+        "required": ["result", "meme_comment", "list"],
+        "additionalProperties": false
+    })
+}
+
+/// 没有启用结构化输出，或宽松解析修补之后使用的 Schema：取值约束不变，
+/// 只要求 `result` 和问题的基本字段，`meme_comment`、`list`、`patch` 可以省略。
+pub fn lenient_check_result_schema() -> Value {
+    let mut schema = check_result_schema();
+    schema["required"] = json!(["result"]);
+    schema["properties"]["list"]["items"]["required"] =
+        json!(["severity", "perspective", "description", "suggestion", "location"]);
+    schema
+}
+
+/// 按 JSON Schema 的一个子集（type/enum/properties/required/items）校验数据，
+/// 返回所有不符合的地方，格式为 `路径: 原因`。
+pub fn validate(value: &Value, schema: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    validate_at(value, schema, "$", &mut errors);
+    errors
+}
+
+fn validate_at(value: &Value, schema: &Value, path: &str, errors: &mut Vec<String>) {
+    if let Some(expected) = schema.get("type") {
+        // `type` 可以是单个类型，也可以是类型数组（如 ["string", "null"]）
+        let expected: Vec<&str> = match expected {
+            Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+            other => other.as_str().into_iter().collect(),
+        };
+        let ok = expected.iter().any(|expected| match *expected {
+            "object" => value.is_object(),
+            "array" => value.is_array(),
+            "string" => value.is_string(),
+            "number" => value.is_number(),
+            "integer" => value.is_i64() || value.is_u64(),
+            "boolean" => value.is_boolean(),
+            "null" => value.is_null(),
+            _ => true,
+        });
+        if !ok {
+            errors.push(Msg::SchemaType.fill(&[
+                ("path", path.to_string()),
+                ("expected", expected.join("/")),
+                ("actual", type_name(value).to_string()),
+            ]));
+            return;
+        }
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            let allowed: Vec<String> = allowed.iter().map(Value::to_string).collect();
+            errors.push(Msg::SchemaEnum.fill(&[
+                ("path", path.to_string()),
+                ("value", value.to_string()),
+                ("allowed", allowed.join(", ")),
+            ]));
+        }
+    }
+
+    if let Some(object) = value.as_object() {
+        if let Some(required) = schema.get("required").and_then(Value::as_array) {
+            for key in required.iter().filter_map(Value::as_str) {
+                if !object.contains_key(key) {
+                    errors.push(Msg::SchemaRequired.fill(&[
+                        ("path", path.to_string()),
+                        ("key", key.to_string()),
+                    ]));
+                }
+            }
+        }
+        if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+            for (key, sub_schema) in properties {
+                if let Some(sub_value) = object.get(key) {
+                    validate_at(sub_value, sub_schema, &format!("{}.{}", path, key), errors);
+                }
+            }
+        }
+    }
+
+    if let (Some(items), Some(array)) = (schema.get("items"), value.as_array()) {
+        for (i, item) in array.iter().enumerate() {
+            validate_at(item, items, &format!("{}[{}]", path, i), errors);
+        }
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue() -> Value {
+        json!({
+            "severity": "high",
+            "perspective": "security",
+            "description": "d",
+            "suggestion": "s",
+            "location": "src/a.rs:1",
+            "patch": null
+        })
+    }
+
+    #[test]
+    fn valid_result_has_no_errors() {
+        let value = json!({ "result": "NO", "meme_comment": "m", "list": [issue()] });
+        assert!(validate(&value, &check_result_schema()).is_empty());
+    }
+
+    #[test]
+    fn errors_point_at_the_offending_path() {
+        let mut bad = issue();
+        bad["severity"] = json!("critical");
+        bad["patch"] = json!(3);
+        let value = json!({ "result": "MAYBE", "meme_comment": "m", "list": [issue(), bad] });
+
+        let errors = validate(&value, &check_result_schema());
+        assert_eq!(errors.len(), 3, "{errors:?}");
+        assert!(errors[0].contains("$.list[1].patch"));
+        assert!(errors[1].contains("$.list[1].severity") && errors[1].contains("critical"));
+        assert!(errors[2].contains("$.result") && errors[2].contains("MAYBE"));
+    }
+
+    #[test]
+    fn missing_required_fields_are_reported() {
+        let errors = validate(&json!({ "result": "YES" }), &check_result_schema());
+        assert_eq!(errors.len(), 2, "{errors:?}");
+        assert!(errors.iter().any(|e| e.contains("meme_comment")));
+        assert!(errors.iter().any(|e| e.contains("list")));
+    }
+
+    #[test]
+    fn type_mismatch_stops_descending() {
+        let value = json!({ "result": "YES", "list": "none" });
+        let errors = validate(&value, &lenient_check_result_schema());
+        assert_eq!(errors.len(), 1, "{errors:?}");
+        assert!(errors[0].contains("$.list"));
+        assert_eq!(validate(&json!([]), &check_result_schema()).len(), 1);
+    }
+
+    #[test]
+    fn lenient_schema_allows_optional_fields_but_keeps_enums() {
+        let schema = lenient_check_result_schema();
+        let mut item = issue();
+        item.as_object_mut().unwrap().remove("patch");
+        assert!(validate(&json!({ "result": "YES" }), &schema).is_empty());
+        assert!(validate(&json!({ "result": "NO", "list": [item] }), &schema).is_empty());
+        assert_eq!(validate(&json!({ "result": "yes" }), &schema).len(), 1);
+    }
+}
@@ -0,0 +1,83 @@
+use crate::config::Config;
+use crate::i18n::Msg;
+use crate::{ai, git, prompts, secrets, usage};
+use anyhow::{Context, Result};
+use colored::*;
+use std::fs;
+use std::path::Path;
+use std::process::exit;
+
+/// `suggest-message` 子命令：根据暂存区生成提交信息并打印到标准输出。
+pub async fn run(config: &Config) -> Result<()> {
+    match generate(config).await {
+        Ok(Some(message)) => {
+            println!("{}", message);
+            Ok(())
+        }
+        Ok(None) => {
+            eprintln!("{}", Msg::NoMonitoredStaged.text().yellow());
+            exit(1);
+        }
+        Err(e) => {
+            eprintln!("{} {}", Msg::GenerateMessageFailed.text().red().bold(), e);
+            exit(1);
+        }
+    }
+}
+
+/// prepare-commit-msg 钩子入口。
+///
+/// 只有在用户没有通过 `-m`/`-F`/模板提供信息，且不是 merge、squash 或 amend 时才预填；
+/// 任何失败都只打印警告，不影响提交。
+pub async fn prepare_commit_msg(config: &Config, file: &Path, source: Option<&str>) -> Result<()> {
+    if source.is_some_and(|s| !s.is_empty()) {
+        return Ok(());
+    }
+
+    if !matches!(git::repo_state(config), Ok(git::RepoState::Clean)) {
+        return Ok(());
+    }
+
+    let message = match generate(config).await {
+        Ok(Some(message)) => message,
+        Ok(None) => return Ok(()),
+        Err(e) => {
+            eprintln!("{} {} {}", Msg::Warning.text().yellow(), Msg::GenerateMessageFailed, e);
+            return Ok(());
+        }
+    };
+
+    // 保留 git 写入的注释（状态说明等），把生成的信息放在最前面
+    let existing = fs::read_to_string(file).unwrap_or_default();
+    fs::write(file, format!("{}\n{}", message, existing))
+        .with_context(|| Msg::CommitMsgWriteFailed.fill(&[("path", format!("{:?}", file))]))?;
+
+    Ok(())
+}
+
+async fn generate(config: &Config) -> Result<Option<String>> {
+    let diff = git::get_staged_diff(config)?;
+    if diff.trim().is_empty() {
+        return Ok(None);
+    }
+    let diff = if config.redact_secrets {
+        secrets::scan(&diff).redacted
+    } else {
+        diff
+    };
+
+    let summary = git::get_staged_summary(config).unwrap_or_default();
+    let system_prompt = prompts::generate_message_prompt(config);
+    let (message, token_usage) =
+        ai::call_message_suggestion(config, system_prompt, diff, &summary).await?;
+    if let Some(usage) = token_usage {
+        let entry = ai::ModelUsage {
+            stage: ai::ModelUsage::REVIEW.to_string(),
+            model: config.model.clone(),
+            usage,
+        };
+        usage::record(config, "suggest-message", &entry);
+    }
+
+    Ok(Some(message))
+}
@@ -0,0 +1,80 @@
+/// 替换模板中的 `{{name}}` 占位符，未知的占位符原样保留。
+///
+/// 只扫描一遍模板，替换进去的值不会再被当作模板解析。多行的值从第二行起
+/// 沿用占位符所在行的缩进，嵌入 JSON 等结构化内容时保持对齐。
+pub fn render(template: &str, vars: &[(&str, String)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let end = start + 2 + len + 2;
+        let name = rest[start + 2..start + 2 + len].trim();
+        out.push_str(&rest[..start]);
+
+        match vars.iter().find(|(key, _)| *key == name) {
+            Some((_, value)) => {
+                let line_start = out.rfind('\n').map_or(0, |i| i + 1);
+                let indent: String = out[line_start..]
+                    .chars()
+                    .take_while(|c| *c == ' ' || *c == '\t')
+                    .collect();
+                out.push_str(&value.replace('\n', &format!("\n{}", indent)));
+            }
+            None => out.push_str(&rest[start..end]),
+        }
+        rest = &rest[end..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&'static str, &str)]) -> Vec<(&'static str, String)> {
+        pairs.iter().map(|(k, v)| (*k, v.to_string())).collect()
+    }
+
+    #[test]
+    fn placeholders_are_replaced() {
+        let out = render("{{a}} and {{ b }}, {{a}}", &vars(&[("a", "1"), ("b", "2")]));
+        assert_eq!(out, "1 and 2, 1");
+    }
+
+    #[test]
+    fn unknown_and_unclosed_placeholders_are_kept() {
+        assert_eq!(render("{{x}} {{a}}", &vars(&[("a", "1")])), "{{x}} 1");
+        assert_eq!(render("{{a}} {{b", &vars(&[("a", "1")])), "1 {{b");
+        assert_eq!(render("{}", &vars(&[])), "{}");
+    }
+
+    #[test]
+    fn values_are_not_rendered_again() {
+        let out = render("{{a}}{{b}}", &vars(&[("a", "{{b}}"), ("b", "x")]));
+        assert_eq!(out, "{{b}}x");
+    }
+
+    #[test]
+    fn multi_line_values_keep_the_indent() {
+        let template = "{\n  \"rules\": {{rules}},\n\t- {{list}}\n}";
+        let out = render(
+            template,
+            &vars(&[("rules", "{\n  \"a\": 1\n}"), ("list", "x\ny")]),
+        );
+        assert_eq!(out, "{\n  \"rules\": {\n    \"a\": 1\n  },\n\t- x\n\ty\n}");
+    }
+
+    #[test]
+    fn non_ascii_text_is_preserved() {
+        let out = render(
+            "审查 {{file}} 中的变更：{{n}} 处",
+            &vars(&[("file", "中文.rs"), ("n", "3")]),
+        );
+        assert_eq!(out, "审查 中文.rs 中的变更：3 处");
+    }
+}
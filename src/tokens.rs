@@ -0,0 +1,138 @@
+use crate::config::Config;
+
+/// 常见模型的上下文窗口（Token 数），按模型名前缀匹配，越具体的前缀越靠前。
+const CONTEXT_WINDOWS: &[(&str, usize)] = &[
+    ("deepseek", 128_000),
+    ("gpt-4.1", 1_047_576),
+    ("gpt-4o", 128_000),
+    ("gpt-4-turbo", 128_000),
+    ("gpt-4-32k", 32_768),
+    ("gpt-4", 8_192),
+    ("gpt-3.5-turbo", 16_385),
+    ("o1", 200_000),
+    ("o3", 200_000),
+    ("o4", 200_000),
+    ("claude", 200_000),
+    ("qwen-long", 1_000_000),
+    ("qwen", 128_000),
+    ("glm-4", 128_000),
+    ("moonshot-v1-8k", 8_192),
+    ("moonshot-v1-32k", 32_768),
+    ("moonshot-v1-128k", 128_000),
+];
+
+/// 未知模型的保守默认值。
+const DEFAULT_CONTEXT_WINDOW: usize = 32_000;
+
+/// 单个 diff 块的最小预算。
+const MIN_CHUNK_BUDGET: usize = 512;
+
+/// 每条消息的角色、分隔符等额外开销（粗略值）。
+const MESSAGE_OVERHEAD: usize = 16;
+
+/// 模型的上下文窗口大小；`override_size` 非 0 时直接使用配置值。
+pub fn context_window(model: &str, override_size: usize) -> usize {
+    if override_size > 0 {
+        return override_size;
+    }
+
+    let model = model.to_lowercase();
+    CONTEXT_WINDOWS
+        .iter()
+        .find(|(prefix, _)| model.starts_with(prefix))
+        .map(|(_, size)| *size)
+        .unwrap_or(DEFAULT_CONTEXT_WINDOW)
+}
+
+/// 单个 diff 块可用的 Token 预算：上下文窗口减去系统提示词、用户消息前缀和预留的输出空间。
+///
+/// 配置了 `AI_CHECK_MAX_CHUNK_TOKENS` 时取两者中较小的值。
+pub fn chunk_budget(config: &Config, system_prompt: &str, user_prefix: &str) -> usize {
+    let window = context_window(&config.model, config.context_window);
+    let used = count_message(&config.model, system_prompt)
+        + count_message(&config.model, user_prefix)
+        + reserved_output(config);
+
+    // 预留空间超过窗口时仍保留一个最小预算，避免无法发送任何内容
+    let available = window.saturating_sub(used).max(MIN_CHUNK_BUDGET);
+
+    match config.max_chunk_tokens {
+        0 => available,
+        limit => limit.min(available),
+    }
+}
+
+/// 为模型输出预留的 Token 数：设置了 `AI_CHECK_MAX_TOKENS` 时以它为准。
+pub fn reserved_output(config: &Config) -> usize {
+    config
+        .max_tokens
+        .map_or(config.reserved_output_tokens, |max| max as usize)
+}
+
+/// 估算一段文本在指定模型下的 Token 数。
+///
+/// 启用 `tokenizer` feature 时使用内置 BPE：gpt-4o / o 系列使用 o200k，其余模型用 cl100k 近似；
+/// 否则按字符类别估算（CJK 约 1 字 1 Token，其他约 4 字符 1 Token）。
+pub fn count(model: &str, text: &str) -> usize {
+    #[cfg(feature = "tokenizer")]
+    {
+        bpe_for(model).encode_ordinary(text).len()
+    }
+    #[cfg(not(feature = "tokenizer"))]
+    {
+        let _ = model;
+        estimate(text)
+    }
+}
+
+/// 一条对话消息（含格式开销）的 Token 数。
+pub fn count_message(model: &str, text: &str) -> usize {
+    count(model, text) + MESSAGE_OVERHEAD
+}
+
+/// 截断文本使其不超过 `budget` 个 Token，在字符边界上截断。
+pub fn truncate(model: &str, text: &str, budget: usize) -> String {
+    if count(model, text) <= budget {
+        return text.to_string();
+    }
+
+    // ends[k] 是前 k + 1 个字符的结束位置；二分查找能放进预算的最长前缀
+    let ends: Vec<usize> = text.char_indices().map(|(i, c)| i + c.len_utf8()).collect();
+    let (mut lo, mut hi) = (0, ends.len());
+    while lo < hi {
+        let mid = (lo + hi).div_ceil(2);
+        if count(model, &text[..ends[mid - 1]]) <= budget {
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+
+    let end = if lo == 0 { 0 } else { ends[lo - 1] };
+    text[..end].to_string()
+}
+
+#[cfg(feature = "tokenizer")]
+fn bpe_for(model: &str) -> &'static tiktoken_rs::CoreBPE {
+    let model = model.to_lowercase();
+    let o200k = ["gpt-4o", "gpt-4.1", "o1", "o3", "o4", "gpt-5"];
+    if o200k.iter().any(|prefix| model.starts_with(prefix)) {
+        tiktoken_rs::o200k_base_singleton()
+    } else {
+        tiktoken_rs::cl100k_base_singleton()
+    }
+}
+
+#[cfg(not(feature = "tokenizer"))]
+fn estimate(text: &str) -> usize {
+    let mut cjk = 0usize;
+    let mut other = 0usize;
+    for c in text.chars() {
+        if matches!(c as u32, 0x2E80..=0x9FFF | 0xAC00..=0xD7AF | 0xF900..=0xFAFF | 0xFF00..=0xFFEF) {
+            cjk += 1;
+        } else {
+            other += 1;
+        }
+    }
+    cjk + other.div_ceil(4)
+}
@@ -0,0 +1,97 @@
+use crate::ai::{self, AiCheckResult, ModelUsage, TriageDecision};
+use crate::config::Config;
+use crate::i18n::Msg;
+use crate::prompts::{self, ReviewPrompt};
+use anyhow::Result;
+use colored::*;
+use serde::Deserialize;
+
+/// 分流模型对一个 diff 块的风险评估。
+#[derive(Deserialize, Debug)]
+pub struct Triage {
+    pub risk: String,
+    #[serde(default)]
+    pub categories: Vec<String>,
+    #[serde(default)]
+    pub reason: String,
+}
+
+/// 两阶段审查一个 diff 块：先由分流模型评估风险，达到阈值的块交给深度审查模型，
+/// 其余仍由主模型审查。分流失败时按高风险处理，宁可多花 Token 也不漏审。
+pub async fn review(config: &Config, prompt: &ReviewPrompt, diff: String) -> Result<AiCheckResult> {
+    let Some(deep) = &config.deep_model else {
+        return ai::call_ai_check(config, prompt, &diff).await;
+    };
+
+    let triage_config = match &config.triage_model {
+        Some(endpoint) => config.with_endpoint(endpoint),
+        None => config.clone(),
+    };
+
+    let (triage, triage_usage) = match ai::call_triage(
+        &triage_config,
+        prompts::generate_triage_prompt(config),
+        diff.clone(),
+    )
+    .await
+    {
+        Ok(res) => res,
+        Err(e) => {
+            eprintln!("{} {} {:#}", Msg::Warning.text().yellow(), Msg::TriageFailed, e);
+            let triage = Triage {
+                risk: "high".to_string(),
+                categories: Vec::new(),
+                reason: Msg::TriageFailedReason.text().to_string(),
+            };
+            (triage, None)
+        }
+    };
+
+    let escalated = rank(&triage.risk) >= rank(&config.triage_escalate);
+    let review_config = if escalated {
+        config.with_endpoint(deep)
+    } else {
+        config.clone()
+    };
+
+    let triage_usage: Vec<ModelUsage> = triage_usage
+        .map(|usage| ModelUsage {
+            stage: ModelUsage::TRIAGE.to_string(),
+            model: triage_config.model.clone(),
+            usage,
+        })
+        .into_iter()
+        .collect();
+
+    // 审查失败时分流消耗的 Token 同样要计入用量
+    let mut result = match ai::call_ai_check(&review_config, prompt, &diff).await {
+        Ok(result) => result,
+        Err(e) => return Err(ai::FailedCheck::wrap(e, triage_usage).into()),
+    };
+
+    if escalated {
+        for entry in &mut result.usage {
+            entry.stage = ModelUsage::DEEP.to_string();
+        }
+    }
+    result.usage.splice(0..0, triage_usage);
+
+    result.triage.push(TriageDecision {
+        risk: triage.risk,
+        categories: triage.categories,
+        reason: triage.reason,
+        model: review_config.model,
+        escalated,
+    });
+
+    Ok(result)
+}
+
+/// 未知的风险等级按 high 处理。
+fn rank(risk: &str) -> u8 {
+    match risk.trim().to_lowercase().as_str() {
+        "low" => 1,
+        "medium" => 2,
+        _ => 3,
+    }
+}
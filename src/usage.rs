@@ -0,0 +1,254 @@
+use crate::ai::{ModelUsage, TokenUsage};
+use crate::config::Config;
+use crate::git;
+use crate::i18n::Msg;
+use anyhow::{Context, Result};
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 内置价格表（美元 / 百万 Token：输入, 输出），可被 `AI_CHECK_PRICES` 覆盖或补充。
+const DEFAULT_PRICES: &[(&str, f64, f64)] = &[
+    ("deepseek-chat", 0.27, 1.10),
+    ("deepseek-reasoner", 0.55, 2.19),
+    ("gpt-4o-mini", 0.15, 0.60),
+    ("gpt-4o", 2.50, 10.00),
+    ("gpt-4.1-mini", 0.40, 1.60),
+    ("gpt-4.1", 2.00, 8.00),
+];
+
+/// 账本中的一条使用记录（`.git/ai_check/usage.jsonl` 的一行）。
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UsageRecord {
+    pub timestamp: u64,
+    pub date: String,
+    pub author: String,
+    pub model: String,
+    pub command: String,
+    /// 本次运行的标识。一次运行可能调用多个模型或多次请求，写入多条记录，
+    /// 汇总时按它统计运行次数；旧记录中没有该字段，每条记录算作一次运行
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub run: String,
+    /// 审查阶段（review/triage/deep），旧记录中没有该字段
+    #[serde(default)]
+    pub stage: String,
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    pub total_tokens: u32,
+    pub cost: Option<f64>,
+    /// 显式配置的模型参数（temperature、seed 等），用于复现
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub params: serde_json::Map<String, serde_json::Value>,
+}
+
+/// 按价格表计算一次调用的费用；模型不在表中时返回 None。
+pub fn cost(config: &Config, model: &str, usage: &TokenUsage) -> Option<f64> {
+    let (input, output) = price_for(config, model)?;
+    Some(
+        (usage.prompt_tokens as f64 * input + usage.completion_tokens as f64 * output)
+            / 1_000_000.0,
+    )
+}
+
+/// 配置中的价格优先；否则在内置表中按最长前缀匹配。
+fn price_for(config: &Config, model: &str) -> Option<(f64, f64)> {
+    if let Some((_, input, output)) = config.prices.iter().find(|(name, _, _)| name == model) {
+        return Some((*input, *output));
+    }
+
+    DEFAULT_PRICES
+        .iter()
+        .filter(|(name, _, _)| model.starts_with(name))
+        .max_by_key(|(name, _, _)| name.len())
+        .map(|(_, input, output)| (*input, *output))
+}
+
+/// 把一次运行的用量追加到本仓库的账本中。写入失败只打印警告，不影响检查结果。
+pub fn record(config: &Config, command: &str, entry: &ModelUsage) {
+    if !config.track_usage {
+        return;
+    }
+
+    if let Err(e) = append(config, command, entry) {
+        eprintln!("{} {} {}", Msg::Warning.text().yellow(), Msg::UsageWriteFailed, e);
+    }
+}
+
+fn append(config: &Config, command: &str, entry: &ModelUsage) -> Result<()> {
+    let (model, usage) = (entry.model.as_str(), &entry.usage);
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+
+    let record = UsageRecord {
+        timestamp,
+        date: utc_date(timestamp),
+        author: author(),
+        model: model.to_string(),
+        command: command.to_string(),
+        run: run_id().to_string(),
+        stage: entry.stage.clone(),
+        prompt_tokens: usage.prompt_tokens,
+        completion_tokens: usage.completion_tokens,
+        total_tokens: usage.total_tokens,
+        cost: cost(config, model, usage),
+        params: crate::ai::model_params(config),
+    };
+
+    let path = ledger_path(config)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).context(Msg::UsageDirFailed.text())?;
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| Msg::UsageOpenFailed.fill(&[("path", format!("{:?}", path))]))?;
+    writeln!(file, "{}", serde_json::to_string(&record)?)?;
+
+    Ok(())
+}
+
+/// 当前进程的运行标识：启动时间（纳秒）和进程号，同一次运行写入的记录共用。
+fn run_id() -> &'static str {
+    static RUN_ID: OnceLock<String> = OnceLock::new();
+    RUN_ID.get_or_init(|| {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default();
+        format!("{:x}-{}", nanos, std::process::id())
+    })
+}
+
+fn ledger_path(config: &Config) -> Result<PathBuf> {
+    Ok(git::backend(config)
+        .git_dir()?
+        .join("ai_check")
+        .join("usage.jsonl"))
+}
+
+/// 提交者身份：优先 user.email，其次 user.name。
+pub fn author() -> String {
+    ["user.email", "user.name"]
+        .iter()
+        .find_map(|key| {
+            let output = Command::new("git").args(["config", key]).output().ok()?;
+            let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            (!value.is_empty()).then_some(value)
+        })
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// 今天的 UTC 日期（YYYY-MM-DD）。
+pub fn today() -> String {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    utc_date(timestamp)
+}
+
+/// 将 Unix 时间戳转换为 UTC 日期（YYYY-MM-DD）。
+fn utc_date(timestamp: u64) -> String {
+    // Howard Hinnant 的 civil_from_days 算法
+    let z = (timestamp / 86_400) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[derive(Default)]
+struct Totals {
+    /// 不同的运行标识
+    runs: HashSet<String>,
+    prompt_tokens: u64,
+    completion_tokens: u64,
+    cost: f64,
+    unpriced: usize,
+}
+
+/// `usage` 子命令：按日期、提交者和模型汇总账本。
+pub fn summarize(config: &Config, days: Option<u64>) -> Result<()> {
+    let path = ledger_path(config)?;
+    if !path.exists() {
+        println!("{}", Msg::UsageEmpty.text().blue());
+        return Ok(());
+    }
+
+    let since = days.map(|d| {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        now.saturating_sub(d * 86_400)
+    });
+
+    let content = fs::read_to_string(&path).context(Msg::UsageReadFailed.text())?;
+    let records: Vec<UsageRecord> = content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .filter(|r: &UsageRecord| since.is_none_or(|s| r.timestamp >= s))
+        .collect();
+
+    println!("{} {:?}", Msg::UsageLedger.text().bold(), path);
+    print_group(Msg::UsageByDate.text(), &records, |r| r.date.clone());
+    print_group(Msg::UsageByAuthor.text(), &records, |r| r.author.clone());
+    print_group(Msg::UsageByModel.text(), &records, |r| r.model.clone());
+
+    Ok(())
+}
+
+fn print_group(title: &str, records: &[UsageRecord], key: impl Fn(&UsageRecord) -> String) {
+    let mut groups: BTreeMap<String, Totals> = BTreeMap::new();
+    for (index, record) in records.iter().enumerate() {
+        let totals = groups.entry(key(record)).or_default();
+        totals.runs.insert(if record.run.is_empty() {
+            format!("#{}", index)
+        } else {
+            record.run.clone()
+        });
+        totals.prompt_tokens += u64::from(record.prompt_tokens);
+        totals.completion_tokens += u64::from(record.completion_tokens);
+        match record.cost {
+            Some(cost) => totals.cost += cost,
+            None => totals.unpriced += 1,
+        }
+    }
+
+    println!("\n{}", title.cyan().bold());
+    println!(
+        "  {:<32} {:>6} {:>12} {:>12} {:>10}",
+        "",
+        Msg::UsageRuns.text(),
+        Msg::UsageInput.text(),
+        Msg::UsageOutput.text(),
+        Msg::UsageCost.text()
+    );
+    for (name, totals) in &groups {
+        let unpriced = if totals.unpriced > 0 {
+            Msg::UsageUnpriced.fill(&[("count", totals.unpriced.to_string())])
+        } else {
+            String::new()
+        };
+        println!(
+            "  {:<32} {:>6} {:>12} {:>12} {:>10.4}{}",
+            name, totals.runs.len(), totals.prompt_tokens, totals.completion_tokens, totals.cost, unpriced
+        );
+    }
+}